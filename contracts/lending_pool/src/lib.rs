@@ -1,28 +1,281 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, token};
+use soroban_sdk::{contract, contractclient, contractevent, contractimpl, contracttype, Address, Env, Vec, token};
+
+#[cfg(test)]
+mod test;
+
+mod tranche;
+
+pub use tranche::{Tranche, TrancheInfo};
 
 #[contracttype]
 #[derive(Clone)]
 pub struct LenderInfo {
     pub deposit_amount: i128,
     pub deposit_timestamp: u64,
-    pub earned_interest: i128,
-    pub share_percentage: u32,  // in basis points (10000 = 100%)
+    pub earned_interest: i128,  // interest paid out so far
+    pub pending_interest: i128, // interest accrued but not yet paid out
+    pub share_percentage: u32,  // of the tranche, in basis points (10000 = 100%)
+    pub tranche: Tranche,
+    pub interest_per_share_paid: i128, // tranche accumulators already applied to this position
+    pub loss_scale_paid: i128,
+    pub epoch: u32,
+    pub lock_until: u64,       // withdrawals before this pay the early-exit penalty
+    pub multiplier_bps: u32,   // yield multiplier of the chosen lock (10000 = 1x)
+}
+
+// Interface an external KYC/compliance contract implements to gate lenders
+#[contractclient(name = "ComplianceClient")]
+pub trait Compliance {
+    fn is_allowed(env: Env, account: Address) -> bool;
+}
+
+// A lock period lenders may choose at deposit time and the yield multiplier it earns
+#[contracttype]
+#[derive(Clone)]
+pub struct LockTier {
+    pub lock_months: u32,
+    pub multiplier_bps: u32,
 }
 
+// A default absorbed by the pool: `covered` came from the insurance reserve, the rest from lenders
+#[contracttype]
+#[derive(Clone)]
+pub struct ReserveDraw {
+    pub loan_id: u64,
+    pub loss: i128,
+    pub covered: i128,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PoolConfig {
+    pub admin: Address,
+    pub loan_manager: Address,
+    pub asset: Address,
+    pub base_rate: u32,
+    pub max_utilization: u32,
+}
+
+// A withdrawal waiting for liquidity. Partially filled requests keep their place in line.
+#[contracttype]
+#[derive(Clone)]
+pub struct WithdrawalRequest {
+    pub request_id: u64,
+    pub lender: Address,
+    pub tranche: Tranche,
+    pub amount: i128, // still to be paid out
+    pub requested_at: u64,
+}
+
+// Queued withdrawals served or skipped per call, so one transaction can't exhaust its budget
+const MAX_QUEUE_PAYOUTS: u32 = 10;
+const SECONDS_PER_MONTH: u64 = 30 * 24 * 60 * 60;
+
 #[contracttype]
 pub enum DataKey {
     TotalLiquidity,
     TotalBorrowed,
     TotalInterestEarned,
-    LenderInfo(Address),
+    LenderInfo(Address, Tranche),
     LoanManagerAddress,
     USDCTokenAddress,
     BaseInterestRate,
     MaxUtilization,
-    AccumulatedInterestPerShare,
     AdminAddress,
+    ReserveFactor,      // bps of interest routed to the treasury
+    TreasuryBalance,    // protocol fees held by the pool, excluded from liquidity
+    InsuranceCut,       // bps of interest routed to the insurance reserve
+    InsuranceReserve,   // first-loss fund, excluded from liquidity
+    ReserveDraws,       // Vec<ReserveDraw>
+    Tranche(Tranche),   // Tranche -> TrancheInfo
+    TrancheWipeout(Tranche, u32), // interest_per_share when losses wiped out the tranche in that epoch
+    WithdrawalQueue,    // Vec<WithdrawalRequest>, oldest first
+    WithdrawalCounter,
+    LockTiers,          // Vec<LockTier>, ascending by lock_months
+    EarlyExitPenalty,   // bps of principal forfeited when withdrawing before the lock ends
+    DepositCap,         // max TotalLiquidity, 0 = uncapped
+    LenderCap,          // max deposit per lender across tranches, 0 = uncapped
+    AllowlistEnabled,
+    Allowlisted(Address),
+    ComplianceContract, // external allowlist consulted instead of Allowlisted when set
+}
+
+#[contractevent]
+pub struct Deposit {
+    #[topic]
+    pub lender: Address,
+    #[topic]
+    pub tranche: Tranche,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct Withdraw {
+    #[topic]
+    pub lender: Address,
+    #[topic]
+    pub tranche: Tranche,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct Borrow {
+    #[topic]
+    pub loan_id: u64,
+    #[topic]
+    pub borrower: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct Repay {
+    #[topic]
+    pub loan_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct OriginationFee {
+    #[topic]
+    pub loan_id: u64,
+    pub fee: i128,
+}
+
+#[contractevent]
+pub struct ReserveFee {
+    #[topic]
+    pub loan_id: u64,
+    pub fee: i128,
+}
+
+#[contractevent]
+pub struct ReserveFactorSet {
+    pub reserve_factor: u32,
+}
+
+#[contractevent]
+pub struct TreasuryWithdraw {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct InsuranceFee {
+    #[topic]
+    pub loan_id: u64,
+    pub fee: i128,
+}
+
+#[contractevent]
+pub struct InsuranceCutSet {
+    pub insurance_cut: u32,
+}
+
+#[contractevent]
+pub struct ReserveContribution {
+    #[topic]
+    pub contributor: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct WriteOff {
+    #[topic]
+    pub loan_id: u64,
+    pub loss: i128,
+    pub covered: i128,
+}
+
+#[contractevent]
+pub struct SeniorTargetRateSet {
+    pub target_rate_bps: u32,
+}
+
+#[contractevent]
+pub struct WithdrawalQueued {
+    #[topic]
+    pub lender: Address,
+    #[topic]
+    pub request_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct WithdrawalCancelled {
+    #[topic]
+    pub lender: Address,
+    pub request_id: u64,
+}
+
+#[contractevent]
+pub struct WithdrawalFilled {
+    #[topic]
+    pub lender: Address,
+    pub request_id: u64,
+}
+
+#[contractevent]
+pub struct LockTiersSet {
+    pub count: u32,
+}
+
+#[contractevent]
+pub struct EarlyExitPenaltySet {
+    pub penalty_bps: u32,
+}
+
+#[contractevent]
+pub struct LockExpired {
+    #[topic]
+    pub lender: Address,
+    #[topic]
+    pub tranche: Tranche,
+    pub deposit_amount: i128,
+}
+
+#[contractevent]
+pub struct EarlyExitPenalty {
+    #[topic]
+    pub lender: Address,
+    #[topic]
+    pub tranche: Tranche,
+    pub penalty: i128,
+}
+
+#[contractevent]
+pub struct DepositCapSet {
+    pub cap: i128,
+}
+
+#[contractevent]
+pub struct LenderCapSet {
+    pub cap: i128,
+}
+
+#[contractevent]
+pub struct AllowlistEnabledSet {
+    pub enabled: bool,
+}
+
+#[contractevent]
+pub struct Allowlisted {
+    #[topic]
+    pub lender: Address,
+    pub allowed: bool,
+}
+
+#[contractevent]
+pub struct ComplianceContractSet {
+    pub compliance: Option<Address>,
+}
+
+#[contractevent]
+pub struct LoanManagerSet {
+    pub loan_manager: Address,
 }
 
 #[contract]
@@ -30,21 +283,14 @@ pub struct LendingPool;
 
 #[contractimpl]
 impl LendingPool {
-    pub fn initialize(
+    // Runs once at deployment
+    pub fn __constructor(
         env: Env,
         admin: Address,
         loan_manager: Address,
         usdc_token: Address,
         base_rate: u32,
     ) {
-        admin.require_auth();
-
-        let existing: Option<Address> = env
-            .storage()
-            .instance()
-            .get(&DataKey::LoanManagerAddress);
-        assert!(existing.is_none(), "Pool already initialized");
-
         env.storage().instance().set(&DataKey::LoanManagerAddress, &loan_manager);
         env.storage().instance().set(&DataKey::USDCTokenAddress, &usdc_token);
         env.storage().instance().set(&DataKey::BaseInterestRate, &base_rate);
@@ -55,26 +301,26 @@ impl LendingPool {
         env.storage().instance().set(&DataKey::AdminAddress, &admin);
     }
 
-    pub fn deposit(env: Env, lender: Address, amount: i128) {
+    // Deposit into a tranche, optionally locked for one of the admin's lock tiers. Topping up
+    // re-prices the whole position at the chosen tier and never shortens an existing lock.
+    pub fn deposit(env: Env, lender: Address, amount: i128, tranche: Tranche, lock_months: u32) {
         lender.require_auth();
         
         assert!(amount > 0, "Amount must be positive");
+        let lock_tier = Self::lock_tier(&env, lock_months);
+        Self::check_deposit_limits(&env, &lender, amount);
         
         // Transfer USDC from lender to contract
         let usdc_token_address: Address = env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap();
         let usdc_token = token::Client::new(&env, &usdc_token_address);
         
-        usdc_token.transfer(&lender, &env.current_contract_address(), &amount);
+        usdc_token.transfer(&lender, env.current_contract_address(), &amount);
         
         // Update lender info
-        let mut lender_info = env.storage().persistent()
-            .get(&DataKey::LenderInfo(lender.clone()))
-            .unwrap_or_else(|| LenderInfo {
-                deposit_amount: 0,
-                deposit_timestamp: env.ledger().timestamp(),
-                earned_interest: 0,
-                share_percentage: 0,
-            });
+        tranche::accrue_senior_coupon(&env);
+        let mut tranche_info = tranche::load(&env, tranche);
+        let mut lender_info = Self::load_lender(&env, &lender, tranche, &tranche_info);
+        tranche::settle(&env, &tranche_info, &mut lender_info);
             
         // Update totals
         let mut total_liquidity: i128 = env
@@ -87,112 +333,123 @@ impl LendingPool {
             .instance()
             .set(&DataKey::TotalLiquidity, &total_liquidity);
 
-        lender_info.deposit_amount += amount;
-        lender_info.share_percentage = if total_liquidity > 0 {
-            ((lender_info.deposit_amount as u128 * 10000) / total_liquidity as u128) as u32
-        } else {
-            10000
-        };
+        let deposit_amount = lender_info.deposit_amount + amount;
+        tranche::reweight(&mut tranche_info, &mut lender_info, deposit_amount, lock_tier.multiplier_bps);
+        tranche::save(&env, tranche, &tranche_info);
+
+        let lock_until = env.ledger().timestamp() + (lock_months as u64) * SECONDS_PER_MONTH;
+        if lock_until > lender_info.lock_until {
+            lender_info.lock_until = lock_until;
+        }
+        lender_info.share_percentage = 
+            ((lender_info.deposit_amount as u128 * 10000) / tranche_info.total_deposits as u128) as u32;
 
         env.storage()
             .persistent()
-            .set(&DataKey::LenderInfo(lender.clone()), &lender_info);
+            .set(&DataKey::LenderInfo(lender.clone(), tranche), &lender_info);
 
-        env.events().publish(("deposit", lender.clone()), amount);
+        (Deposit { lender, tranche, amount }).publish(&env);
+        
+        Self::process_queue(&env);
     }
     
-    // Lender withdraws USDC
-    pub fn withdraw(env: Env, lender: Address, amount: i128) {
+    // Lender withdraws USDC from a tranche, together with any interest owed. If the pool cannot
+    // cover it right now, or other lenders are already waiting, the request joins the back of
+    // the withdrawal queue and is paid out as liquidity returns.
+    pub fn withdraw(env: Env, lender: Address, amount: i128, tranche: Tranche) {
         lender.require_auth();
         
         assert!(amount > 0, "Amount must be positive");
         
+        tranche::accrue_senior_coupon(&env);
+        let tranche_info = tranche::load(&env, tranche);
         let mut lender_info: LenderInfo = env.storage().persistent()
-            .get(&DataKey::LenderInfo(lender.clone()))
+            .get(&DataKey::LenderInfo(lender.clone(), tranche))
             .expect("Lender not found");
-            
-        assert!(lender_info.deposit_amount >= amount, "Insufficient balance");
-        
-        // Get available liquidity
-        let total_liquidity: i128 = env.storage().instance().get(&DataKey::TotalLiquidity).unwrap_or(0);
-        let total_borrowed: i128 = env.storage().instance().get(&DataKey::TotalBorrowed).unwrap_or(0);
-        let available_liquidity = total_liquidity - total_borrowed;
-        
-        assert!(amount <= available_liquidity, "Insufficient liquidity in the pool");
+        tranche::settle(&env, &tranche_info, &mut lender_info);
         
-        // Transfer USDC back to lender
-        let usdc_token_address: Address = env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap();
-        let usdc_token = token::Client::new(&env, &usdc_token_address);
-        
-        // Calculate and transfer earned interest first
-        let current_interest_per_share: i128 = env.storage().instance()
-            .get(&DataKey::AccumulatedInterestPerShare)
-            .unwrap_or(0);
-            
-        // Calculate pending interest based on the lender's current deposit
-        let pending_interest = (lender_info.deposit_amount as i128 * current_interest_per_share) / 1_000_000_000
-            - lender_info.earned_interest;
+        let queued = Self::queued_for(&env, &lender, tranche);
+        assert!(lender_info.deposit_amount - queued >= amount, "Insufficient balance");
+        assert!(
+            env.ledger().timestamp() >= lender_info.lock_until || Self::get_early_exit_penalty(env.clone()) > 0,
+            "Deposit is locked"
+        );
         
-        // Transfer principal and interest
-        if pending_interest > 0 {
-            // Transfer principal + interest
-            usdc_token.transfer(
-                &env.current_contract_address(), 
-                &lender, 
-                &(amount + pending_interest)
-            );
-            
-            // Update total interest earned
-            let total_interest_earned: i128 = env.storage().instance()
-                .get(&DataKey::TotalInterestEarned)
-                .unwrap_or(0);
-            
-            if total_interest_earned >= pending_interest {
-                env.storage().instance().set(
-                    &DataKey::TotalInterestEarned,
-                    &(total_interest_earned - pending_interest)
-                );
-            }
-            
-            lender_info.earned_interest += pending_interest;
-        } else {
-            // Only transfer principal if no interest is due
-            usdc_token.transfer(&env.current_contract_address(), &lender, &amount);
+        let mut queue = Self::get_withdrawal_queue(env.clone());
+        if queue.is_empty() && amount <= Self::get_available_liquidity(env.clone()) {
+            Self::pay_out(&env, &lender, tranche, amount);
+            return;
         }
         
-        // Update lender info
-        lender_info.deposit_amount -= amount;
+        let request_id: u64 = env.storage().instance().get(&DataKey::WithdrawalCounter).unwrap_or(0) + 1;
+        env.storage().instance().set(&DataKey::WithdrawalCounter, &request_id);
         
-        // Update total liquidity
-        let new_total_liquidity = total_liquidity - amount;
-        env.storage().instance().set(&DataKey::TotalLiquidity, &new_total_liquidity);
+        queue.push_back(WithdrawalRequest {
+            request_id,
+            lender: lender.clone(),
+            tranche,
+            amount,
+            requested_at: env.ledger().timestamp(),
+        });
+        env.storage().instance().set(&DataKey::WithdrawalQueue, &queue);
         
-        // Update share percentage if there's still liquidity
-        if new_total_liquidity > 0 {
-            lender_info.share_percentage = ((lender_info.deposit_amount as u128 * 10000) / new_total_liquidity as u128) as u32;
-        } else {
-            lender_info.share_percentage = 0;
-        }
+        (WithdrawalQueued { lender, request_id, amount }).publish(&env);
+    }
+    
+    // Drop a queued withdrawal; the funds stay deposited
+    pub fn cancel_withdrawal(env: Env, lender: Address, request_id: u64) {
+        lender.require_auth();
         
-        // Save updated lender info
-        env.storage().persistent().set(&DataKey::LenderInfo(lender.clone()), &lender_info);
+        let mut queue = Self::get_withdrawal_queue(env.clone());
+        let index = Self::queue_index(&queue, request_id).expect("Withdrawal request not found");
+        assert!(queue.get(index).unwrap().lender == lender, "Not the request owner");
+        queue.remove(index);
+        env.storage().instance().set(&DataKey::WithdrawalQueue, &queue);
         
-        // Emit withdraw event
-        env.events().publish(("withdraw", lender.clone()), amount);
+        (WithdrawalCancelled { lender, request_id }).publish(&env);
+    }
+    
+    // Pay out queued withdrawals from idle liquidity. Runs after deposits and repayments;
+    // anyone may call it to push the queue along.
+    pub fn process_withdrawals(env: Env) {
+        Self::process_queue(&env);
     }
     
-    // Borrow from pool (called by LoanManager only)
-    pub fn borrow(env: Env, amount: i128, borrower: Address, loan_id: u64) {
-        // Only loan manager should call this. Because loan manager calls this as part of an admin-
-        // initiated flow, we rely on the stored address and do not expose this in the UI.
-        // (Soroban 23 contracts cannot easily assert cross-contract auth without recording auth data.)
-        let _loan_manager: Address = env
+    pub fn get_withdrawal_queue(env: Env) -> Vec<WithdrawalRequest> {
+        env.storage()
+            .instance()
+            .get(&DataKey::WithdrawalQueue)
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    // 1-based position of a request in the queue
+    pub fn get_queue_position(env: Env, request_id: u64) -> u32 {
+        let queue = Self::get_withdrawal_queue(env);
+        Self::queue_index(&queue, request_id).expect("Withdrawal request not found") + 1
+    }
+    
+    // Total amount waiting in the queue
+    pub fn get_queued_withdrawals(env: Env) -> i128 {
+        let mut total: i128 = 0;
+        for request in Self::get_withdrawal_queue(env).iter() {
+            total += request.amount;
+        }
+        total
+    }
+    
+    // Borrow from pool (called by LoanManager only). The origination fee is withheld from
+    // the disbursement and credited to the treasury; the borrower owes the full amount.
+    pub fn borrow(env: Env, amount: i128, fee: i128, borrower: Address, loan_id: u64) {
+        // Only loan manager can call this
+        let loan_manager: Address = env
             .storage()
             .instance()
             .get(&DataKey::LoanManagerAddress)
             .expect("Loan manager not configured");
+        loan_manager.require_auth();
 
         assert!(amount > 0, "Amount must be positive");
+        assert!(fee >= 0 && fee < amount, "Invalid origination fee");
         
         // Check available liquidity
         let total_liquidity: i128 = env.storage().instance().get(&DataKey::TotalLiquidity).unwrap_or(0);
@@ -204,62 +461,194 @@ impl LendingPool {
         // Update total borrowed
         env.storage().instance().set(&DataKey::TotalBorrowed, &(total_borrowed + amount));
         
-        // Transfer USDC to borrower
+        // Transfer USDC to borrower, net of the origination fee
         let usdc_token_address: Address = env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap();
         let usdc_token = token::Client::new(&env, &usdc_token_address);
-        usdc_token.transfer(&env.current_contract_address(), &borrower, &amount);
+        usdc_token.transfer(&env.current_contract_address(), &borrower, &(amount - fee));
+        
+        if fee > 0 {
+            Self::credit_treasury(&env, fee);
+            (OriginationFee { loan_id, fee }).publish(&env);
+        }
         
-        env.events().publish(("borrow", loan_id, borrower), amount);
+        (Borrow { loan_id, borrower, amount }).publish(&env);
     }
     
     // Repay to pool (called by LoanManager only)
     pub fn repay(env: Env, principal: i128, interest: i128, loan_id: u64) {
-        // Only loan manager should call this. See note in `borrow`.
-        let _loan_manager: Address = env
+        // Only loan manager can call this
+        let loan_manager: Address = env
             .storage()
             .instance()
             .get(&DataKey::LoanManagerAddress)
             .expect("Loan manager not configured");
+        loan_manager.require_auth();
 
         assert!(principal >= 0 && interest >= 0, "Amounts must be non-negative");
         
         let total_borrowed: i128 = env.storage().instance().get(&DataKey::TotalBorrowed).unwrap_or(0);
         let total_interest_earned: i128 = env.storage().instance().get(&DataKey::TotalInterestEarned).unwrap_or(0);
         
+        // Skim the protocol reserve factor before lenders are paid
+        let reserve_factor: u32 = env.storage().instance().get(&DataKey::ReserveFactor).unwrap_or(0);
+        let reserve_fee = (interest * reserve_factor as i128) / 10000;
+        if reserve_fee > 0 {
+            Self::credit_treasury(&env, reserve_fee);
+            (ReserveFee { loan_id, fee: reserve_fee }).publish(&env);
+        }
+        
+        // Fund the first-loss insurance reserve
+        let insurance_cut: u32 = env.storage().instance().get(&DataKey::InsuranceCut).unwrap_or(0);
+        let insurance_fee = (interest * insurance_cut as i128) / 10000;
+        if insurance_fee > 0 {
+            Self::credit_insurance(&env, insurance_fee);
+            (InsuranceFee { loan_id, fee: insurance_fee }).publish(&env);
+        }
+        let lender_interest = interest - reserve_fee - insurance_fee;
+        
+        // Distribute interest to lenders through the tranche waterfall. With no lenders to
+        // pay, it goes to the insurance reserve instead.
+        let undistributed = tranche::distribute_interest(&env, lender_interest);
+        if undistributed > 0 {
+            Self::credit_insurance(&env, undistributed);
+        }
+        
         // Update totals
         env.storage().instance().set(&DataKey::TotalBorrowed, &(total_borrowed - principal));
-        env.storage().instance().set(&DataKey::TotalInterestEarned, &(total_interest_earned + interest));
+        env.storage().instance().set(
+            &DataKey::TotalInterestEarned,
+            &(total_interest_earned + lender_interest - undistributed)
+        );
         
-        // Distribute interest to lenders proportionally
-        if interest > 0 {
-            let total_liquidity: i128 = env.storage().instance().get(&DataKey::TotalLiquidity).unwrap_or(1);
-            
-            if total_liquidity > 0 {
-                // In a real implementation, you would typically have a way to iterate through all lenders
-                // For this example, we'll store the total interest and distribute it when lenders withdraw
-                // or provide a separate claim function
-                
-                // Store the interest to be distributed
-                let interest_per_share = (interest as u128).checked_mul(1_000_000_000)  // For better precision
-                    .map(|v| v / (total_liquidity as u128))
-                    .unwrap_or(0) as i128;
-                
-                // Store the current interest per share
-                let mut current_interest_per_share: i128 = env.storage().instance()
-                    .get(&DataKey::AccumulatedInterestPerShare)
-                    .unwrap_or(0);
-                    
-                current_interest_per_share += interest_per_share;
-                env.storage().instance().set(
-                    &DataKey::AccumulatedInterestPerShare,
-                    &current_interest_per_share
-                );
-                
-                // The actual interest will be calculated when lenders withdraw or claim
-                // by comparing their last claimed interest per share with the current one
-            }
+        (Repay { loan_id, amount: principal + interest }).publish(&env);
+        
+        Self::process_queue(&env);
+    }
+    
+    // Set the share of interest routed to the treasury (admin only)
+    pub fn set_reserve_factor(env: Env, reserve_factor: u32) {
+        Self::require_admin(&env);
+        assert!(reserve_factor <= 5000, "Reserve factor too high");
+        
+        env.storage().instance().set(&DataKey::ReserveFactor, &reserve_factor);
+        (ReserveFactorSet { reserve_factor }).publish(&env);
+    }
+    
+    pub fn get_reserve_factor(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::ReserveFactor).unwrap_or(0)
+    }
+    
+    pub fn get_treasury_balance(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TreasuryBalance).unwrap_or(0)
+    }
+    
+    // Withdraw accumulated protocol fees (admin only)
+    pub fn withdraw_treasury(env: Env, to: Address, amount: i128) {
+        Self::require_admin(&env);
+        assert!(amount > 0, "Amount must be positive");
+        
+        let treasury = Self::get_treasury_balance(env.clone());
+        assert!(amount <= treasury, "Insufficient treasury balance");
+        env.storage().instance().set(&DataKey::TreasuryBalance, &(treasury - amount));
+        
+        let usdc_token_address: Address = env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap();
+        let usdc_token = token::Client::new(&env, &usdc_token_address);
+        usdc_token.transfer(&env.current_contract_address(), &to, &amount);
+        
+        (TreasuryWithdraw { to, amount }).publish(&env);
+    }
+    
+    // Route a share of interest to the insurance reserve (admin only)
+    pub fn set_insurance_cut(env: Env, insurance_cut: u32) {
+        Self::require_admin(&env);
+        assert!(insurance_cut <= 5000, "Insurance cut too high");
+        
+        env.storage().instance().set(&DataKey::InsuranceCut, &insurance_cut);
+        (InsuranceCutSet { insurance_cut }).publish(&env);
+    }
+    
+    pub fn get_insurance_cut(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::InsuranceCut).unwrap_or(0)
+    }
+    
+    // Anyone may top up the insurance reserve directly
+    pub fn contribute_reserve(env: Env, contributor: Address, amount: i128) {
+        contributor.require_auth();
+        assert!(amount > 0, "Amount must be positive");
+        
+        let usdc_token_address: Address = env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap();
+        let usdc_token = token::Client::new(&env, &usdc_token_address);
+        usdc_token.transfer(&contributor, env.current_contract_address(), &amount);
+        
+        Self::credit_insurance(&env, amount);
+        (ReserveContribution { contributor, amount }).publish(&env);
+    }
+    
+    // Write off a defaulted loan (called by LoanManager only). The insurance reserve absorbs
+    // the loss first; anything left goes through the tranche loss waterfall.
+    pub fn write_off(env: Env, loan_id: u64, principal_lost: i128) {
+        let loan_manager: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::LoanManagerAddress)
+            .expect("Loan manager not configured");
+        loan_manager.require_auth();
+        
+        assert!(principal_lost >= 0, "Loss must be non-negative");
+        if principal_lost == 0 {
+            return;
+        }
+        
+        let total_borrowed: i128 = env.storage().instance().get(&DataKey::TotalBorrowed).unwrap_or(0);
+        let loss = if principal_lost < total_borrowed { principal_lost } else { total_borrowed };
+        env.storage().instance().set(&DataKey::TotalBorrowed, &(total_borrowed - loss));
+        
+        let reserve = Self::get_insurance_reserve(env.clone());
+        let covered = if loss < reserve { loss } else { reserve };
+        env.storage().instance().set(&DataKey::InsuranceReserve, &(reserve - covered));
+        
+        // Junior lenders absorb the uncovered loss before senior lenders
+        let uncovered = loss - covered;
+        if uncovered > 0 {
+            let absorbed = tranche::absorb_loss(&env, uncovered);
+            let total_liquidity: i128 = env.storage().instance().get(&DataKey::TotalLiquidity).unwrap_or(0);
+            env.storage().instance().set(&DataKey::TotalLiquidity, &(total_liquidity - absorbed));
         }
-        env.events().publish(("repay", loan_id), principal + interest);
+        
+        let mut draws = Self::get_reserve_draws(env.clone());
+        draws.push_back(ReserveDraw {
+            loan_id,
+            loss,
+            covered,
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().instance().set(&DataKey::ReserveDraws, &draws);
+        
+        (WriteOff { loan_id, loss, covered }).publish(&env);
+    }
+    
+    pub fn get_insurance_reserve(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::InsuranceReserve).unwrap_or(0)
+    }
+    
+    // Insurance reserve as a share of outstanding loans, in basis points. Saturates at
+    // u32::MAX when the reserve dwarfs the loan book.
+    pub fn get_coverage_ratio(env: Env) -> u32 {
+        let total_borrowed: i128 = env.storage().instance().get(&DataKey::TotalBorrowed).unwrap_or(0);
+        if total_borrowed == 0 {
+            return 10000;
+        }
+        let reserve = Self::get_insurance_reserve(env);
+        let ratio = (reserve as u128 * 10000) / total_borrowed as u128;
+        ratio.min(u32::MAX as u128) as u32
+    }
+    
+    // History of defaults absorbed by the pool
+    pub fn get_reserve_draws(env: Env) -> Vec<ReserveDraw> {
+        env.storage()
+            .instance()
+            .get(&DataKey::ReserveDraws)
+            .unwrap_or(Vec::new(&env))
     }
     
     // Get available liquidity
@@ -269,16 +658,199 @@ impl LendingPool {
         total_liquidity - total_borrowed
     }
     
-    // Get lender info
-    pub fn get_lender_info(env: Env, lender: Address) -> LenderInfo {
-        env.storage().persistent()
-            .get(&DataKey::LenderInfo(lender))
-            .unwrap_or_else(|| LenderInfo {
-                deposit_amount: 0,
-                deposit_timestamp: 0,
-                earned_interest: 0,
-                share_percentage: 0,
-            })
+    // Repoint borrow, repay and write-off calls at a new LoanManager (admin only)
+    pub fn set_loan_manager(env: Env, loan_manager: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::LoanManagerAddress, &loan_manager);
+        (LoanManagerSet { loan_manager }).publish(&env);
+    }
+    
+    pub fn get_config(env: Env) -> PoolConfig {
+        PoolConfig {
+            admin: env.storage().instance().get(&DataKey::AdminAddress).expect("Admin not configured"),
+            loan_manager: env.storage().instance().get(&DataKey::LoanManagerAddress).unwrap(),
+            asset: Self::get_asset(env.clone()),
+            base_rate: env.storage().instance().get(&DataKey::BaseInterestRate).unwrap_or(0),
+            max_utilization: env.storage().instance().get(&DataKey::MaxUtilization).unwrap_or(0),
+        }
+    }
+    
+    // Token this pool lends out; LoanManager keys its pool registry on it
+    pub fn get_asset(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap()
+    }
+
+    // Get lender info for one tranche, with interest and losses applied
+    pub fn get_lender_info(env: Env, lender: Address, tranche: Tranche) -> LenderInfo {
+        let tranche_info = tranche::load(&env, tranche);
+        let mut lender_info = Self::load_lender(&env, &lender, tranche, &tranche_info);
+        tranche::settle(&env, &tranche_info, &mut lender_info);
+        lender_info
+    }
+    
+    pub fn get_tranche_info(env: Env, tranche: Tranche) -> TrancheInfo {
+        tranche::load(&env, tranche)
+    }
+    
+    // Annual yield senior lenders receive ahead of junior lenders (admin only)
+    pub fn set_senior_target_rate(env: Env, target_rate_bps: u32) {
+        Self::require_admin(&env);
+        
+        // Close out accrual at the old rate first
+        tranche::accrue_senior_coupon(&env);
+        let mut senior = tranche::load(&env, Tranche::Senior);
+        senior.target_rate_bps = target_rate_bps;
+        tranche::save(&env, Tranche::Senior, &senior);
+        
+        (SeniorTargetRateSet { target_rate_bps }).publish(&env);
+    }
+    
+    // Lock periods offered to lenders (admin only). The first tier must be the unlocked one.
+    pub fn set_lock_tiers(env: Env, tiers: Vec<LockTier>) {
+        Self::require_admin(&env);
+        
+        assert!(!tiers.is_empty(), "Lock tiers required");
+        let first = tiers.get(0).unwrap();
+        assert!(
+            first.lock_months == 0 && first.multiplier_bps == tranche::BASE_MULTIPLIER_BPS,
+            "First tier must be unlocked at 1x"
+        );
+        for i in 1..tiers.len() {
+            let previous = tiers.get(i - 1).unwrap();
+            let tier = tiers.get(i).unwrap();
+            assert!(previous.lock_months < tier.lock_months, "Lock tiers must be ascending");
+            assert!(previous.multiplier_bps <= tier.multiplier_bps, "Multipliers must not decrease");
+        }
+        
+        env.storage().instance().set(&DataKey::LockTiers, &tiers);
+        (LockTiersSet { count: tiers.len() }).publish(&env);
+    }
+    
+    // Defaults to unlocked (1x), 3 months (1.1x), 6 months (1.25x) and 12 months (1.5x)
+    pub fn get_lock_tiers(env: Env) -> Vec<LockTier> {
+        env.storage()
+            .instance()
+            .get(&DataKey::LockTiers)
+            .unwrap_or(Vec::from_array(
+                &env,
+                [
+                    LockTier { lock_months: 0, multiplier_bps: 10000 },
+                    LockTier { lock_months: 3, multiplier_bps: 11000 },
+                    LockTier { lock_months: 6, multiplier_bps: 12500 },
+                    LockTier { lock_months: 12, multiplier_bps: 15000 },
+                ],
+            ))
+    }
+    
+    // Share of principal forfeited on early withdrawal (admin only). Zero forbids early exits.
+    pub fn set_early_exit_penalty(env: Env, penalty_bps: u32) {
+        Self::require_admin(&env);
+        assert!(penalty_bps <= 5000, "Penalty too high");
+        
+        env.storage().instance().set(&DataKey::EarlyExitPenalty, &penalty_bps);
+        (EarlyExitPenaltySet { penalty_bps }).publish(&env);
+    }
+    
+    pub fn get_early_exit_penalty(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::EarlyExitPenalty).unwrap_or(0)
+    }
+    
+    // Cap on total pool deposits (admin only). Zero removes the cap.
+    pub fn set_deposit_cap(env: Env, cap: i128) {
+        Self::require_admin(&env);
+        assert!(cap >= 0, "Cap must be non-negative");
+        
+        env.storage().instance().set(&DataKey::DepositCap, &cap);
+        (DepositCapSet { cap }).publish(&env);
+    }
+    
+    pub fn get_deposit_cap(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::DepositCap).unwrap_or(0)
+    }
+    
+    // Cap on each lender's deposits across both tranches (admin only). Zero removes the cap.
+    pub fn set_lender_cap(env: Env, cap: i128) {
+        Self::require_admin(&env);
+        assert!(cap >= 0, "Cap must be non-negative");
+        
+        env.storage().instance().set(&DataKey::LenderCap, &cap);
+        (LenderCapSet { cap }).publish(&env);
+    }
+    
+    pub fn get_lender_cap(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::LenderCap).unwrap_or(0)
+    }
+    
+    // Only accept deposits from allowlisted lenders (admin only)
+    pub fn set_allowlist_enabled(env: Env, enabled: bool) {
+        Self::require_admin(&env);
+        
+        env.storage().instance().set(&DataKey::AllowlistEnabled, &enabled);
+        (AllowlistEnabledSet { enabled }).publish(&env);
+    }
+    
+    pub fn is_allowlist_enabled(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::AllowlistEnabled).unwrap_or(false)
+    }
+    
+    // Add or remove a lender from the pool's own allowlist (admin only)
+    pub fn set_allowlisted(env: Env, lender: Address, allowed: bool) {
+        Self::require_admin(&env);
+        
+        if allowed {
+            env.storage().persistent().set(&DataKey::Allowlisted(lender.clone()), &true);
+        } else {
+            env.storage().persistent().remove(&DataKey::Allowlisted(lender.clone()));
+        }
+        (Allowlisted { lender, allowed }).publish(&env);
+    }
+    
+    // Delegate allowlist checks to an external compliance contract, or back to the pool's own
+    // list with `None` (admin only)
+    pub fn set_compliance_contract(env: Env, compliance: Option<Address>) {
+        Self::require_admin(&env);
+        
+        match &compliance {
+            Some(address) => env.storage().instance().set(&DataKey::ComplianceContract, address),
+            None => env.storage().instance().remove(&DataKey::ComplianceContract),
+        }
+        (ComplianceContractSet { compliance }).publish(&env);
+    }
+    
+    pub fn get_compliance_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ComplianceContract)
+    }
+    
+    // Whether a lender may deposit under the current allowlist settings
+    pub fn is_lender_allowed(env: Env, lender: Address) -> bool {
+        if !Self::is_allowlist_enabled(env.clone()) {
+            return true;
+        }
+        match Self::get_compliance_contract(env.clone()) {
+            Some(compliance) => ComplianceClient::new(&env, &compliance).is_allowed(&lender),
+            None => env.storage().persistent().has(&DataKey::Allowlisted(lender)),
+        }
+    }
+    
+    // Drop the yield boost of a position whose lock has ended. Anyone may call this so that
+    // expired locks stop diluting the rest of the tranche.
+    pub fn expire_lock(env: Env, lender: Address, tranche: Tranche) {
+        tranche::accrue_senior_coupon(&env);
+        let mut tranche_info = tranche::load(&env, tranche);
+        let mut lender_info: LenderInfo = env.storage().persistent()
+            .get(&DataKey::LenderInfo(lender.clone(), tranche))
+            .expect("Lender not found");
+        
+        assert!(env.ledger().timestamp() >= lender_info.lock_until, "Lock has not ended");
+        assert!(lender_info.multiplier_bps != tranche::BASE_MULTIPLIER_BPS, "No boost to expire");
+        
+        tranche::settle(&env, &tranche_info, &mut lender_info);
+        let deposit_amount = lender_info.deposit_amount;
+        tranche::reweight(&mut tranche_info, &mut lender_info, deposit_amount, tranche::BASE_MULTIPLIER_BPS);
+        tranche::save(&env, tranche, &tranche_info);
+        env.storage().persistent().set(&DataKey::LenderInfo(lender.clone(), tranche), &lender_info);
+        
+        (LockExpired { lender, tranche, deposit_amount }).publish(&env);
     }
     
     // Get utilization rate
@@ -292,4 +864,211 @@ impl LendingPool {
         
         ((total_borrowed as u128 * 10000) / total_liquidity as u128) as u32
     }
+    
+    // Internal: Stored lender position, or a fresh one checkpointed at the tranche's accumulators
+    fn load_lender(env: &Env, lender: &Address, tranche: Tranche, tranche_info: &TrancheInfo) -> LenderInfo {
+        env.storage().persistent()
+            .get(&DataKey::LenderInfo(lender.clone(), tranche))
+            .unwrap_or_else(|| LenderInfo {
+                deposit_amount: 0,
+                deposit_timestamp: env.ledger().timestamp(),
+                earned_interest: 0,
+                pending_interest: 0,
+                share_percentage: 0,
+                tranche,
+                interest_per_share_paid: tranche_info.interest_per_share,
+                loss_scale_paid: tranche_info.loss_scale,
+                epoch: tranche_info.epoch,
+                lock_until: 0,
+                multiplier_bps: tranche::BASE_MULTIPLIER_BPS,
+            })
+    }
+    
+    // Internal: Transfer principal and interest owed to a lender and update the books. The
+    // caller has checked the balance and the pool's liquidity. Principal paid out before the
+    // lock ends forfeits the early-exit penalty to the insurance reserve.
+    fn pay_out(env: &Env, lender: &Address, tranche: Tranche, amount: i128) {
+        tranche::accrue_senior_coupon(env);
+        let mut tranche_info = tranche::load(env, tranche);
+        let mut lender_info = Self::load_lender(env, lender, tranche, &tranche_info);
+        tranche::settle(env, &tranche_info, &mut lender_info);
+        
+        let locked = env.ledger().timestamp() < lender_info.lock_until;
+        let penalty = if locked {
+            (amount * Self::get_early_exit_penalty(env.clone()) as i128) / 10000
+        } else {
+            0
+        };
+        if penalty > 0 {
+            Self::credit_insurance(env, penalty);
+            (EarlyExitPenalty { lender: lender.clone(), tranche, penalty }).publish(env);
+        }
+        
+        let usdc_token_address: Address = env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap();
+        let usdc_token = token::Client::new(env, &usdc_token_address);
+        
+        let pending_interest = lender_info.pending_interest;
+        usdc_token.transfer(
+            &env.current_contract_address(), 
+            lender, 
+            &(amount - penalty + pending_interest)
+        );
+        
+        if pending_interest > 0 {
+            // Update total interest earned
+            let total_interest_earned: i128 = env.storage().instance()
+                .get(&DataKey::TotalInterestEarned)
+                .unwrap_or(0);
+            
+            if total_interest_earned >= pending_interest {
+                env.storage().instance().set(
+                    &DataKey::TotalInterestEarned,
+                    &(total_interest_earned - pending_interest)
+                );
+            }
+            
+            lender_info.earned_interest += pending_interest;
+            lender_info.pending_interest = 0;
+        }
+        
+        // Update lender info; an expired lock stops earning its boost
+        let multiplier_bps = if locked { lender_info.multiplier_bps } else { tranche::BASE_MULTIPLIER_BPS };
+        let deposit_amount = lender_info.deposit_amount - amount;
+        tranche::reweight(&mut tranche_info, &mut lender_info, deposit_amount, multiplier_bps);
+        tranche::save(env, tranche, &tranche_info);
+        
+        // Update total liquidity
+        let total_liquidity: i128 = env.storage().instance().get(&DataKey::TotalLiquidity).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TotalLiquidity, &(total_liquidity - amount));
+        
+        // Update share percentage if there's still liquidity in the tranche
+        if tranche_info.total_deposits > 0 {
+            lender_info.share_percentage = 
+                ((lender_info.deposit_amount as u128 * 10000) / tranche_info.total_deposits as u128) as u32;
+        } else {
+            lender_info.share_percentage = 0;
+        }
+        
+        env.storage().persistent().set(&DataKey::LenderInfo(lender.clone(), tranche), &lender_info);
+        
+        (Withdraw { lender: lender.clone(), tranche, amount }).publish(env);
+    }
+    
+    // Internal: Serve the withdrawal queue in order until liquidity runs out. The head request
+    // is filled partially when the pool can't cover all of it.
+    // Requests still inside a lock that may not be exited early are skipped but keep their place.
+    fn process_queue(env: &Env) {
+        let mut queue = Self::get_withdrawal_queue(env.clone());
+        let early_exit_allowed = Self::get_early_exit_penalty(env.clone()) > 0;
+        let mut head = 0u32;
+        let mut served = 0u32;
+        
+        while head < queue.len() && served < MAX_QUEUE_PAYOUTS {
+            let available = Self::get_available_liquidity(env.clone());
+            if available <= 0 {
+                break;
+            }
+            
+            let mut request = queue.get(head).unwrap();
+            served += 1;
+            
+            // Losses written off since the request was queued may have shrunk the deposit
+            let tranche_info = tranche::load(env, request.tranche);
+            let mut lender_info = Self::load_lender(env, &request.lender, request.tranche, &tranche_info);
+            tranche::settle(env, &tranche_info, &mut lender_info);
+            if env.ledger().timestamp() < lender_info.lock_until && !early_exit_allowed {
+                head += 1;
+                continue;
+            }
+            if request.amount > lender_info.deposit_amount {
+                request.amount = lender_info.deposit_amount;
+            }
+            
+            let fill = if request.amount < available { request.amount } else { available };
+            if fill > 0 {
+                Self::pay_out(env, &request.lender, request.tranche, fill);
+            }
+            
+            request.amount -= fill;
+            if request.amount > 0 {
+                queue.set(head, request);
+                break;
+            }
+            queue.remove(head);
+            (WithdrawalFilled { lender: request.lender, request_id: request.request_id }).publish(env);
+        }
+        
+        env.storage().instance().set(&DataKey::WithdrawalQueue, &queue);
+    }
+    
+    // Internal: Enforce the allowlist, the pool cap and the per-lender cap on a new deposit
+    fn check_deposit_limits(env: &Env, lender: &Address, amount: i128) {
+        assert!(Self::is_lender_allowed(env.clone(), lender.clone()), "Lender not allowlisted");
+        
+        let deposit_cap = Self::get_deposit_cap(env.clone());
+        if deposit_cap > 0 {
+            let total_liquidity: i128 = env.storage().instance().get(&DataKey::TotalLiquidity).unwrap_or(0);
+            assert!(total_liquidity + amount <= deposit_cap, "Pool deposit cap reached");
+        }
+        
+        let lender_cap = Self::get_lender_cap(env.clone());
+        if lender_cap > 0 {
+            let senior = Self::get_lender_info(env.clone(), lender.clone(), Tranche::Senior);
+            let junior = Self::get_lender_info(env.clone(), lender.clone(), Tranche::Junior);
+            assert!(
+                senior.deposit_amount + junior.deposit_amount + amount <= lender_cap,
+                "Lender deposit cap reached"
+            );
+        }
+    }
+    
+    // Internal: Lock tier for a requested lock period
+    fn lock_tier(env: &Env, lock_months: u32) -> LockTier {
+        for tier in Self::get_lock_tiers(env.clone()).iter() {
+            if tier.lock_months == lock_months {
+                return tier;
+            }
+        }
+        panic!("Unsupported lock period");
+    }
+    
+    // Internal: Amount a lender already has waiting in the queue for one tranche
+    fn queued_for(env: &Env, lender: &Address, tranche: Tranche) -> i128 {
+        let mut total: i128 = 0;
+        for request in Self::get_withdrawal_queue(env.clone()).iter() {
+            if request.lender == *lender && request.tranche == tranche {
+                total += request.amount;
+            }
+        }
+        total
+    }
+    
+    fn queue_index(queue: &Vec<WithdrawalRequest>, request_id: u64) -> Option<u32> {
+        queue
+            .iter()
+            .position(|request| request.request_id == request_id)
+            .map(|index| index as u32)
+    }
+    
+    // Internal: Add to the insurance reserve
+    fn credit_insurance(env: &Env, amount: i128) {
+        let reserve: i128 = env.storage().instance().get(&DataKey::InsuranceReserve).unwrap_or(0);
+        env.storage().instance().set(&DataKey::InsuranceReserve, &(reserve + amount));
+    }
+    
+    // Internal: Require auth from the stored admin
+    fn require_admin(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminAddress)
+            .expect("Admin not configured");
+        admin.require_auth();
+    }
+    
+    // Internal: Add protocol fees to the treasury balance
+    fn credit_treasury(env: &Env, amount: i128) {
+        let treasury: i128 = env.storage().instance().get(&DataKey::TreasuryBalance).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TreasuryBalance, &(treasury + amount));
+    }
 }
\ No newline at end of file
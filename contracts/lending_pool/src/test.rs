@@ -0,0 +1,422 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    token::{StellarAssetClient, TokenClient},
+    contract, contractimpl, Address, Env, IntoVal,
+};
+
+use crate::{LendingPool, LendingPoolClient, Tranche};
+
+// Compliance contract that allows only the account it was constructed with
+#[contract]
+struct OneAccountCompliance;
+
+#[contractimpl]
+impl OneAccountCompliance {
+    pub fn __constructor(env: Env, account: Address) {
+        env.storage().instance().set(&0u32, &account);
+    }
+
+    pub fn is_allowed(env: Env, account: Address) -> bool {
+        env.storage().instance().get::<u32, Address>(&0u32) == Some(account)
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    client: LendingPoolClient<'a>,
+    usdc: StellarAssetClient<'a>,
+    token: TokenClient<'a>,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let loan_manager = Address::generate(&env);
+    let usdc_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
+
+    let client = LendingPoolClient::new(
+        &env,
+        &env.register(LendingPool, (&admin, &loan_manager, &usdc_address, 500u32)),
+    );
+
+    Setup {
+        usdc: StellarAssetClient::new(&env, &usdc_address),
+        token: TokenClient::new(&env, &usdc_address),
+        env,
+        client,
+    }
+}
+
+// A fresh lender that has deposited `amount` into `tranche`
+fn lender(s: &Setup, amount: i128, tranche: Tranche) -> Address {
+    let lender = Address::generate(&s.env);
+    s.usdc.mint(&lender, &amount);
+    s.client.deposit(&lender, &amount, &tranche, &0u32);
+    lender
+}
+
+// A fresh junior lender that has deposited `amount` locked for `lock_months`
+fn locked_lender(s: &Setup, amount: i128, lock_months: u32) -> Address {
+    let lender = Address::generate(&s.env);
+    s.usdc.mint(&lender, &amount);
+    s.client.deposit(&lender, &amount, &Tranche::Junior, &lock_months);
+    lender
+}
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+#[test]
+fn borrow_and_repay_require_the_loan_manager() {
+    let s = setup();
+    lender(&s, 1_000, Tranche::Junior);
+    let attacker = Address::generate(&s.env);
+
+    // The attacker authorizes the calls, but the loan manager does not
+    let borrow = s
+        .client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &s.client.address,
+                fn_name: "borrow",
+                args: (500i128, 0i128, attacker.clone(), 1u64).into_val(&s.env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_borrow(&500, &0, &attacker, &1);
+    assert!(borrow.is_err());
+    assert_eq!(s.token.balance(&attacker), 0);
+    assert_eq!(s.client.get_available_liquidity(), 1_000);
+
+    let repay = s
+        .client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &s.client.address,
+                fn_name: "repay",
+                args: (0i128, 500i128, 1u64).into_val(&s.env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_repay(&0, &500, &1);
+    assert!(repay.is_err());
+
+    let write_off = s
+        .client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &s.client.address,
+                fn_name: "write_off",
+                args: (1u64, 500i128).into_val(&s.env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_write_off(&1, &500);
+    assert!(write_off.is_err());
+}
+
+#[test]
+fn insurance_cut_funds_the_reserve() {
+    let s = setup();
+    lender(&s, 1_000, Tranche::Junior);
+    let borrower = Address::generate(&s.env);
+    s.client.set_insurance_cut(&1000u32);
+
+    s.client.borrow(&500, &0, &borrower, &1);
+    s.client.repay(&0, &100, &1);
+
+    assert_eq!(s.client.get_insurance_reserve(), 10);
+    // 10 held against 500 outstanding
+    assert_eq!(s.client.get_coverage_ratio(), 200);
+}
+
+#[test]
+fn write_off_draws_on_the_reserve_before_lenders() {
+    let s = setup();
+    let lender = lender(&s, 1_000, Tranche::Junior);
+    let borrower = Address::generate(&s.env);
+    let contributor = Address::generate(&s.env);
+    s.usdc.mint(&contributor, &100);
+    s.client.contribute_reserve(&contributor, &100);
+
+    s.client.borrow(&500, &0, &borrower, &1);
+    s.client.write_off(&1, &300);
+
+    assert_eq!(s.client.get_insurance_reserve(), 0);
+    assert_eq!(s.client.get_lender_info(&lender, &Tranche::Junior).deposit_amount, 800);
+
+    let draws = s.client.get_reserve_draws();
+    assert_eq!(draws.len(), 1);
+    let draw = draws.get(0).unwrap();
+    assert_eq!((draw.loan_id, draw.loss, draw.covered), (1, 300, 100));
+}
+
+#[test]
+fn coverage_ratio_saturates() {
+    let s = setup();
+    lender(&s, 1_000, Tranche::Junior);
+    let borrower = Address::generate(&s.env);
+    let contributor = Address::generate(&s.env);
+    s.usdc.mint(&contributor, &1_000_000_000_000);
+    s.client.contribute_reserve(&contributor, &1_000_000_000_000);
+
+    s.client.borrow(&1, &0, &borrower, &1);
+
+    assert_eq!(s.client.get_coverage_ratio(), u32::MAX);
+}
+
+#[test]
+fn consecutive_losses_compound_on_what_the_first_left() {
+    let s = setup();
+    let a = lender(&s, 100, Tranche::Junior);
+    let b = lender(&s, 100, Tranche::Junior);
+
+    // Lend out enough to lose, then write off 100 and then 50 of it
+    let borrower = Address::generate(&s.env);
+    s.client.borrow(&150, &0, &borrower, &1);
+    s.client.write_off(&1, &100);
+    s.client.write_off(&2, &50);
+
+    // The second loss halves the 100 the first one left, so each lender keeps 25
+    for lender in [&a, &b] {
+        assert_eq!(s.client.get_lender_info(lender, &Tranche::Junior).deposit_amount, 25);
+    }
+
+    s.client.withdraw(&a, &25, &Tranche::Junior);
+    s.client.withdraw(&b, &25, &Tranche::Junior);
+    assert_eq!(s.token.balance(&a), 25);
+    assert_eq!(s.token.balance(&b), 25);
+}
+
+#[test]
+fn junior_absorbs_losses_before_senior() {
+    let s = setup();
+    let senior = lender(&s, 1_000, Tranche::Senior);
+    let junior = lender(&s, 200, Tranche::Junior);
+    let borrower = Address::generate(&s.env);
+
+    s.client.borrow(&1_000, &0, &borrower, &1);
+    s.client.write_off(&1, &500);
+
+    assert_eq!(s.client.get_lender_info(&junior, &Tranche::Junior).deposit_amount, 0);
+    assert_eq!(s.client.get_lender_info(&senior, &Tranche::Senior).deposit_amount, 700);
+    assert_eq!(s.client.get_tranche_info(&Tranche::Junior).epoch, 1);
+}
+
+#[test]
+fn near_total_loss_wipes_out_the_tranche() {
+    let s = setup();
+    let wiped = lender(&s, 10_000_000_000, Tranche::Junior);
+    let borrower = Address::generate(&s.env);
+
+    // Leaves 1 of 10^10, which the loss scale cannot represent
+    s.client.borrow(&10_000_000_000, &0, &borrower, &1);
+    s.client.write_off(&1, &9_999_999_999);
+
+    let info = s.client.get_tranche_info(&Tranche::Junior);
+    assert_eq!(info.epoch, 1);
+    assert_eq!(info.total_deposits, 0);
+    assert_eq!(s.client.get_lender_info(&wiped, &Tranche::Junior).deposit_amount, 0);
+
+    // Later deposits start from a fresh scale and earn interest normally
+    let fresh = lender(&s, 1_000, Tranche::Junior);
+    s.client.borrow(&500, &0, &borrower, &2);
+    s.client.repay(&500, &100, &2);
+    let info = s.client.get_lender_info(&fresh, &Tranche::Junior);
+    assert_eq!(info.deposit_amount, 1_000);
+    assert_eq!(info.pending_interest, 100);
+}
+
+#[test]
+fn interest_with_no_lenders_goes_to_the_insurance_reserve() {
+    let s = setup();
+    let contributor = Address::generate(&s.env);
+    s.usdc.mint(&contributor, &1_000);
+    s.client.contribute_reserve(&contributor, &1_000);
+
+    s.client.repay(&0, &100, &1);
+
+    assert_eq!(s.client.get_insurance_reserve(), 1_100);
+    assert_eq!(s.client.get_tranche_info(&Tranche::Senior).total_interest_earned, 0);
+    assert_eq!(s.client.get_tranche_info(&Tranche::Junior).total_interest_earned, 0);
+}
+
+#[test]
+fn withdrawals_beyond_liquidity_queue_and_fill_in_order() {
+    let s = setup();
+    let a = lender(&s, 600, Tranche::Junior);
+    let b = lender(&s, 400, Tranche::Junior);
+    let borrower = Address::generate(&s.env);
+    s.client.borrow(&900, &0, &borrower, &1);
+
+    // Only 100 is idle: a is queued, and b queues behind a even though 100 would cover it
+    s.client.withdraw(&a, &300, &Tranche::Junior);
+    s.client.withdraw(&b, &100, &Tranche::Junior);
+    let queue = s.client.get_withdrawal_queue();
+    assert_eq!(queue.len(), 2);
+    assert_eq!(s.client.get_queued_withdrawals(), 400);
+    assert_eq!(s.client.get_queue_position(&queue.get(1).unwrap().request_id), 2);
+
+    // Processing fills the head request partially and keeps it first in line
+    s.client.process_withdrawals();
+    assert_eq!(s.token.balance(&a), 100);
+    assert_eq!(s.client.get_withdrawal_queue().get(0).unwrap().amount, 200);
+
+    // Repayments returning liquidity pay the rest out in order
+    s.usdc.mint(&s.client.address, &300);
+    s.client.repay(&300, &0, &1);
+    assert_eq!(s.token.balance(&a), 300);
+    assert_eq!(s.token.balance(&b), 100);
+    assert!(s.client.get_withdrawal_queue().is_empty());
+}
+
+#[test]
+fn queued_amounts_are_reserved_and_can_be_cancelled() {
+    let s = setup();
+    let a = lender(&s, 500, Tranche::Junior);
+    let other = Address::generate(&s.env);
+    let borrower = Address::generate(&s.env);
+    s.client.borrow(&500, &0, &borrower, &1);
+
+    s.client.withdraw(&a, &400, &Tranche::Junior);
+    let request_id = s.client.get_withdrawal_queue().get(0).unwrap().request_id;
+
+    // Only 100 of the deposit is not already queued
+    assert!(s.client.try_withdraw(&a, &200, &Tranche::Junior).is_err());
+    assert!(s.client.try_cancel_withdrawal(&other, &request_id).is_err());
+
+    s.client.cancel_withdrawal(&a, &request_id);
+    assert!(s.client.get_withdrawal_queue().is_empty());
+    assert_eq!(s.client.get_lender_info(&a, &Tranche::Junior).deposit_amount, 500);
+    assert!(s.client.try_get_queue_position(&request_id).is_err());
+}
+
+#[test]
+fn locked_deposits_earn_their_multiplier() {
+    let s = setup();
+    let unlocked = lender(&s, 1_000, Tranche::Junior);
+    let locked = locked_lender(&s, 1_000, 12);
+    let borrower = Address::generate(&s.env);
+
+    s.client.borrow(&1_000, &0, &borrower, &1);
+    s.client.repay(&0, &250, &1);
+
+    // 1x against 1.5x splits the interest 100 to 150
+    assert_eq!(s.client.get_lender_info(&unlocked, &Tranche::Junior).pending_interest, 100);
+    assert_eq!(s.client.get_lender_info(&locked, &Tranche::Junior).pending_interest, 150);
+    assert!(s.client.try_deposit(&locked, &1, &Tranche::Junior, &7u32).is_err());
+}
+
+#[test]
+fn early_exits_pay_the_penalty_or_are_refused() {
+    let s = setup();
+    let locked = locked_lender(&s, 1_000, 6);
+
+    assert!(s.client.try_withdraw(&locked, &100, &Tranche::Junior).is_err());
+
+    s.client.set_early_exit_penalty(&1000u32);
+    s.client.withdraw(&locked, &100, &Tranche::Junior);
+    assert_eq!(s.token.balance(&locked), 90);
+    assert_eq!(s.client.get_insurance_reserve(), 10);
+
+    // Once the lock ends the rest comes out in full
+    s.env.ledger().with_mut(|ledger| ledger.timestamp += 6 * MONTH);
+    s.client.withdraw(&locked, &900, &Tranche::Junior);
+    assert_eq!(s.token.balance(&locked), 990);
+}
+
+#[test]
+fn queued_locked_withdrawals_wait_for_the_lock_without_a_penalty() {
+    let s = setup();
+    s.client.set_early_exit_penalty(&1000u32);
+    let locked = locked_lender(&s, 500, 3);
+    let unlocked = lender(&s, 500, Tranche::Junior);
+    let borrower = Address::generate(&s.env);
+    s.client.borrow(&1_000, &0, &borrower, &1);
+
+    s.client.withdraw(&locked, &200, &Tranche::Junior);
+    s.client.withdraw(&unlocked, &200, &Tranche::Junior);
+
+    // With early exits switched off the locked request is skipped but keeps its place
+    s.client.set_early_exit_penalty(&0u32);
+    s.usdc.mint(&s.client.address, &1_000);
+    s.client.repay(&1_000, &0, &1);
+    assert_eq!(s.token.balance(&locked), 0);
+    assert_eq!(s.token.balance(&unlocked), 200);
+    let queue = s.client.get_withdrawal_queue();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.get(0).unwrap().lender, locked);
+
+    // It is paid in full once the lock ends
+    s.env.ledger().with_mut(|ledger| ledger.timestamp += 3 * MONTH);
+    s.client.process_withdrawals();
+    assert_eq!(s.token.balance(&locked), 200);
+    assert!(s.client.get_withdrawal_queue().is_empty());
+}
+
+#[test]
+fn expired_locks_lose_their_boost() {
+    let s = setup();
+    let locked = locked_lender(&s, 1_000, 3);
+
+    assert!(s.client.try_expire_lock(&locked, &Tranche::Junior).is_err());
+    s.env.ledger().with_mut(|ledger| ledger.timestamp += 3 * MONTH);
+    s.client.expire_lock(&locked, &Tranche::Junior);
+
+    assert_eq!(s.client.get_lender_info(&locked, &Tranche::Junior).multiplier_bps, 10000);
+    assert_eq!(s.client.get_tranche_info(&Tranche::Junior).total_weighted, 1_000);
+}
+
+#[test]
+fn deposit_caps_limit_the_pool_and_each_lender() {
+    let s = setup();
+    s.client.set_deposit_cap(&1_000);
+    s.client.set_lender_cap(&600);
+
+    let a = lender(&s, 400, Tranche::Senior);
+    s.usdc.mint(&a, &300);
+    // The lender cap counts both tranches
+    assert!(s.client.try_deposit(&a, &300, &Tranche::Junior, &0u32).is_err());
+    s.client.deposit(&a, &200, &Tranche::Junior, &0u32);
+
+    let b = lender(&s, 400, Tranche::Junior);
+    s.usdc.mint(&b, &1);
+    assert!(s.client.try_deposit(&b, &1, &Tranche::Junior, &0u32).is_err());
+
+    // Lifting the caps reopens deposits
+    s.client.set_deposit_cap(&0);
+    s.client.deposit(&b, &1, &Tranche::Junior, &0u32);
+    assert_eq!(s.client.get_lender_info(&b, &Tranche::Junior).deposit_amount, 401);
+}
+
+#[test]
+fn allowlist_gates_deposits() {
+    let s = setup();
+    let allowed = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    s.usdc.mint(&allowed, &200);
+    s.usdc.mint(&other, &200);
+
+    s.client.set_allowlist_enabled(&true);
+    s.client.set_allowlisted(&allowed, &true);
+    s.client.deposit(&allowed, &100, &Tranche::Junior, &0u32);
+    assert!(s.client.try_deposit(&other, &100, &Tranche::Junior, &0u32).is_err());
+
+    // An external compliance contract replaces the pool's own list
+    let compliance = s.env.register(OneAccountCompliance, (other.clone(),));
+    s.client.set_compliance_contract(&Some(compliance.clone()));
+    assert!(s.client.is_lender_allowed(&other));
+    assert!(!s.client.is_lender_allowed(&allowed));
+    s.client.deposit(&other, &100, &Tranche::Junior, &0u32);
+
+    s.client.set_compliance_contract(&None);
+    s.client.set_allowlisted(&allowed, &false);
+    assert!(!s.client.is_lender_allowed(&allowed));
+
+    s.client.set_allowlist_enabled(&false);
+    assert!(s.client.is_lender_allowed(&allowed));
+}
@@ -0,0 +1,211 @@
+use soroban_sdk::{ contracttype, Env };
+
+use crate::{ DataKey, LenderInfo };
+
+// Fixed-point scale for the per-share interest accumulator and the loss scale
+pub const SHARE_PRECISION: i128 = 1_000_000_000;
+// Yield multiplier of an unlocked deposit
+pub const BASE_MULTIPLIER_BPS: u32 = 10_000;
+const SECONDS_PER_YEAR: i128 = 365 * 24 * 60 * 60;
+
+// Senior lenders are paid their target yield first; junior lenders take the residual
+// interest and absorb losses first.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tranche {
+    Senior = 0,
+    Junior = 1,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrancheInfo {
+    pub total_deposits: i128,
+    pub total_weighted: i128, // deposits scaled by their lock multipliers; interest is shared on this
+    pub target_rate_bps: u32, // senior only: annual yield paid ahead of junior
+    pub coupon_owed: i128, // senior only: target yield accrued but not yet paid
+    pub last_accrual: u64,
+    pub interest_per_share: i128, // per unit of deposit before losses, scaled by SHARE_PRECISION
+    pub loss_scale: i128, // fraction of every deposit left after losses, scaled by SHARE_PRECISION
+    pub epoch: u32, // bumped when losses wipe out the tranche, which resets `loss_scale`
+    pub total_interest_earned: i128,
+    pub total_losses: i128,
+}
+
+pub fn load(env: &Env, tranche: Tranche) -> TrancheInfo {
+    env.storage()
+        .instance()
+        .get(&DataKey::Tranche(tranche))
+        .unwrap_or(TrancheInfo {
+            total_deposits: 0,
+            total_weighted: 0,
+            target_rate_bps: 0,
+            coupon_owed: 0,
+            last_accrual: env.ledger().timestamp(),
+            interest_per_share: 0,
+            loss_scale: SHARE_PRECISION,
+            epoch: 0,
+            total_interest_earned: 0,
+            total_losses: 0,
+        })
+}
+
+pub fn save(env: &Env, tranche: Tranche, info: &TrancheInfo) {
+    env.storage().instance().set(&DataKey::Tranche(tranche), info);
+}
+
+// Accrue the senior coupon up to the current ledger time
+pub fn accrue_senior_coupon(env: &Env) {
+    let mut senior = load(env, Tranche::Senior);
+    let now = env.ledger().timestamp();
+    if now > senior.last_accrual && senior.total_deposits > 0 {
+        let elapsed = (now - senior.last_accrual) as i128;
+        senior.coupon_owed +=
+            (senior.total_deposits * (senior.target_rate_bps as i128) * elapsed) /
+            (SECONDS_PER_YEAR * 10000);
+    }
+    senior.last_accrual = now;
+    save(env, Tranche::Senior, &senior);
+}
+
+// Interest waterfall: the senior coupon first, the rest to junior. If a tranche is empty the
+// other one receives its share. Returns what could not be credited because both are empty.
+pub fn distribute_interest(env: &Env, amount: i128) -> i128 {
+    if amount <= 0 {
+        return 0;
+    }
+    accrue_senior_coupon(env);
+
+    let mut senior = load(env, Tranche::Senior);
+    let mut junior = load(env, Tranche::Junior);
+    if senior.total_deposits == 0 && junior.total_deposits == 0 {
+        return amount;
+    }
+
+    let mut to_senior = if senior.coupon_owed < amount { senior.coupon_owed } else { amount };
+    let mut to_junior = amount - to_senior;
+    if junior.total_deposits == 0 {
+        to_senior += to_junior;
+        to_junior = 0;
+    } else if senior.total_deposits == 0 {
+        to_junior += to_senior;
+        to_senior = 0;
+    }
+    senior.coupon_owed = if senior.coupon_owed > to_senior { senior.coupon_owed - to_senior } else { 0 };
+
+    credit_interest(&mut senior, to_senior);
+    credit_interest(&mut junior, to_junior);
+
+    save(env, Tranche::Senior, &senior);
+    save(env, Tranche::Junior, &junior);
+    0
+}
+
+// Loss waterfall: junior deposits absorb losses first, then senior. Returns the amount applied.
+pub fn absorb_loss(env: &Env, amount: i128) -> i128 {
+    if amount <= 0 {
+        return 0;
+    }
+    accrue_senior_coupon(env);
+
+    let mut junior = load(env, Tranche::Junior);
+    let mut senior = load(env, Tranche::Senior);
+
+    let junior_loss = if amount < junior.total_deposits { amount } else { junior.total_deposits };
+    let remaining = amount - junior_loss;
+    let senior_loss = if remaining < senior.total_deposits { remaining } else { senior.total_deposits };
+
+    let applied =
+        apply_loss(env, Tranche::Junior, &mut junior, junior_loss) +
+        apply_loss(env, Tranche::Senior, &mut senior, senior_loss);
+
+    save(env, Tranche::Junior, &junior);
+    save(env, Tranche::Senior, &senior);
+
+    applied
+}
+
+// Bring a lender position up to date with its tranche's accumulators. Interest is earned on
+// the lock-weighted deposit, losses are taken on the deposit itself. Both accumulators are kept
+// per unit of deposit before losses, so a position's share of interest and losses does not
+// depend on the order they landed in or on how often it is settled.
+pub fn settle(env: &Env, info: &TrancheInfo, lender_info: &mut LenderInfo) {
+    let deposit = lender_info.deposit_amount;
+    let weighted = weight(deposit, lender_info.multiplier_bps);
+
+    if lender_info.epoch == info.epoch {
+        lender_info.pending_interest +=
+            (weighted * (info.interest_per_share - lender_info.interest_per_share_paid)) /
+            lender_info.loss_scale_paid;
+        lender_info.deposit_amount = (deposit * info.loss_scale) / lender_info.loss_scale_paid;
+    } else {
+        // The tranche was wiped out since this position last settled: it keeps the interest
+        // earned up to the wipe-out and nothing of the deposit
+        let wiped_at: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TrancheWipeout(lender_info.tranche, lender_info.epoch))
+            .unwrap();
+        lender_info.pending_interest +=
+            (weighted * (wiped_at - lender_info.interest_per_share_paid)) / lender_info.loss_scale_paid;
+        lender_info.deposit_amount = 0;
+    }
+
+    lender_info.interest_per_share_paid = info.interest_per_share;
+    lender_info.loss_scale_paid = info.loss_scale;
+    lender_info.epoch = info.epoch;
+}
+
+// Move a settled position to a new deposit size and multiplier, keeping the tranche totals in step
+pub fn reweight(info: &mut TrancheInfo, lender_info: &mut LenderInfo, deposit: i128, multiplier_bps: u32) {
+    let old_weight = weight(lender_info.deposit_amount, lender_info.multiplier_bps);
+    let new_weight = weight(deposit, multiplier_bps);
+
+    info.total_deposits += deposit - lender_info.deposit_amount;
+    info.total_weighted += new_weight - old_weight;
+    if info.total_weighted < 0 {
+        info.total_weighted = 0;
+    }
+
+    lender_info.deposit_amount = deposit;
+    lender_info.multiplier_bps = multiplier_bps;
+}
+
+pub fn weight(deposit: i128, multiplier_bps: u32) -> i128 {
+    (deposit * (multiplier_bps as i128)) / (BASE_MULTIPLIER_BPS as i128)
+}
+
+fn credit_interest(info: &mut TrancheInfo, amount: i128) {
+    if amount <= 0 || info.total_weighted <= 0 {
+        return;
+    }
+    info.interest_per_share += (amount * info.loss_scale) / info.total_weighted;
+    info.total_interest_earned += amount;
+}
+
+// Every deposit in the tranche shrinks by the same fraction. Returns the amount applied, which
+// is the whole tranche when the loss leaves too little to represent.
+fn apply_loss(env: &Env, tranche: Tranche, info: &mut TrancheInfo, amount: i128) -> i128 {
+    if amount <= 0 || info.total_deposits <= 0 {
+        return 0;
+    }
+    let loss_scale = (info.loss_scale * (info.total_deposits - amount)) / info.total_deposits;
+    let applied = if loss_scale > 0 {
+        info.loss_scale = loss_scale;
+        // Every position shrinks by the same fraction, so the weighted total does too
+        info.total_weighted -= (info.total_weighted * amount) / info.total_deposits;
+        amount
+    } else {
+        // A scale of zero could never grow back, so start a new epoch for later deposits
+        env.storage()
+            .instance()
+            .set(&DataKey::TrancheWipeout(tranche, info.epoch), &info.interest_per_share);
+        info.epoch += 1;
+        info.loss_scale = SHARE_PRECISION;
+        info.total_weighted = 0;
+        info.total_deposits
+    };
+    info.total_deposits -= applied;
+    info.total_losses += applied;
+    applied
+}
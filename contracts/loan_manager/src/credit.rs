@@ -0,0 +1,47 @@
+use soroban_sdk::contracttype;
+
+use remitlend_math::credit::{
+    INCOME_MULTIPLE_BPS,
+    MAX_ACTIVE_LOANS,
+    MAX_DEBT_TO_INCOME_BPS,
+    MIN_SCORE,
+};
+
+// Per-borrower exposure rules checked when a loan is requested and again when it is approved
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreditPolicy {
+    pub income_multiple_bps: u32, // limit at a perfect score, as a multiple of monthly remittances
+    pub min_score: u32, // borrowers below this score get no credit
+    pub max_active_loans: u32, // pending and active loans per borrower
+    pub max_debt_to_income_bps: u32, // monthly installments over monthly remittances
+}
+
+// Up to 3x monthly remittances, three open loans and 40% of remittance income in installments
+pub fn default_policy() -> CreditPolicy {
+    CreditPolicy {
+        income_multiple_bps: INCOME_MULTIPLE_BPS,
+        min_score: MIN_SCORE,
+        max_active_loans: MAX_ACTIVE_LOANS,
+        max_debt_to_income_bps: MAX_DEBT_TO_INCOME_BPS,
+    }
+}
+
+pub fn validate_policy(policy: &CreditPolicy) {
+    assert!(policy.income_multiple_bps > 0, "Income multiple must be positive");
+    assert!(policy.min_score <= 100, "Min score out of range");
+    assert!(policy.max_active_loans > 0, "Max active loans must be positive");
+    assert!(policy.max_debt_to_income_bps > 0, "Max debt-to-income must be positive");
+}
+
+// Total principal a borrower may owe, scaled down linearly with their score
+pub fn credit_limit(policy: &CreditPolicy, monthly_amount: i128, score: u32) -> i128 {
+    remitlend_math::credit::credit_limit(
+        policy.income_multiple_bps,
+        policy.min_score,
+        monthly_amount,
+        score
+    )
+}
+
+pub use remitlend_math::credit::{ debt_to_income_bps, MISSES_TO_DEFAULT };
@@ -0,0 +1,31 @@
+use soroban_sdk::contracttype;
+
+// What happens to the interest of a deferred cycle
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeferredInterest {
+    Capitalize = 0, // added to the balance and repaid to the pool as interest
+    Waive = 1, // forgiven; the pool earns nothing for the cycle
+}
+
+// Payment holiday rules checked when a borrower requests a deferral
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeferralPolicy {
+    pub max_per_loan: u32,
+    pub auto_approve_min_score: u32, // above 100 means every request waits for the admin
+    pub interest: DeferredInterest,
+}
+
+// Two deferrals per loan, granted automatically from a score of 80, interest capitalized
+pub fn default_policy() -> DeferralPolicy {
+    DeferralPolicy {
+        max_per_loan: 2,
+        auto_approve_min_score: 80,
+        interest: DeferredInterest::Capitalize,
+    }
+}
+
+pub fn validate_policy(policy: &DeferralPolicy) {
+    assert!(policy.max_per_loan <= 12, "Too many deferrals per loan");
+}
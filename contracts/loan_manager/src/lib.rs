@@ -2,22 +2,55 @@
 
 use soroban_sdk::{ contract, contractimpl, contracttype, Address, Env, Vec, token, contractevent };
 
+mod credit;
+mod deferral;
+mod pricing;
+mod repayment;
+
+#[cfg(test)]
+mod test;
+
+pub use credit::CreditPolicy;
+pub use deferral::{ DeferralPolicy, DeferredInterest };
+pub use pricing::{
+    DurationBand,
+    LoanQuote,
+    PaymentFrequency,
+    PricingTable,
+    ScheduledPayment,
+    ScoreBand,
+    SizeBand,
+};
+pub use repayment::{ ExcessPayment, PaymentAllocation };
+
+// The wasm build calls RemittanceNFT and LendingPool through their interfaces; native builds
+// (tests included) link the crates directly so they don't need the wasm built first
+#[cfg(target_family = "wasm")]
 mod nft {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/release/remittance_nft.wasm"
     );
 }
 
+#[cfg(not(target_family = "wasm"))]
+mod nft {
+    pub use ::remittance_nft::{ RemittanceData, RemittanceNFTClient as Client };
+}
+
+#[cfg(target_family = "wasm")]
 mod pool {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/release/lending_pool.wasm"
     );
 }
 
-// use remittance_nft::{RemittanceNFTClient};
+#[cfg(not(target_family = "wasm"))]
+mod pool {
+    pub use ::lending_pool::LendingPoolClient as Client;
+}
 
 #[contracttype]
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum LoanStatus {
     Pending = 0,
     Active = 1,
@@ -36,12 +69,46 @@ pub struct Loan {
     pub total_repaid: i128,
     pub interest_rate: u32, // APR in basis points
     pub duration_months: u32,
-    pub monthly_payment: i128,
+    pub frequency: PaymentFrequency,
+    pub installments: u32, // cycles in the term at `frequency`
+    pub installment_amount: i128, // due each cycle
     pub start_timestamp: u64,
     pub next_payment_due: u64,
     pub status: LoanStatus,
     pub payments_made: u32,
     pub payments_missed: u32,
+    pub origination_fee: i128, // withheld from the disbursement at approval
+    pub asset: Address, // token the loan is borrowed and repaid in
+    pub restructurings: Vec<Restructuring>, // term changes since approval, oldest first
+    pub deferrals_used: u32,
+    pub deferred_until: u64, // misses are not reported before this while a deferral runs
+    pub capitalized_interest: i128, // deferred interest included in outstanding_balance
+    pub fees_due: i128, // late fees not yet paid
+    pub accrued_interest: i128, // interest accrued for the current and any missed cycles
+    pub installment_paid: i128, // paid toward the current cycle's installment
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RestructuringKind {
+    Restructured = 0, // hardship terms set by the admin or risk manager
+    Refinanced = 1, // repriced at the borrower's request after their score improved
+}
+
+// One re-amortization of a loan's outstanding balance
+#[contracttype]
+#[derive(Clone)]
+pub struct Restructuring {
+    pub kind: RestructuringKind,
+    pub timestamp: u64,
+    pub outstanding_balance: i128, // balance re-amortized over the new term
+    pub previous_rate: u32,
+    pub new_rate: u32,
+    pub previous_duration_months: u32,
+    pub new_duration_months: u32,
+    pub previous_installment_amount: i128,
+    pub new_installment_amount: i128,
+    pub payments_missed: u32, // misses forgiven by the restructuring
 }
 
 #[contracttype]
@@ -54,55 +121,192 @@ pub enum DataKey {
     OracleContract,
     USDCTokenAddress,
     AdminAddress,
+    PricingTable,
+    OriginationFeeBps,
+    AssetPool(Address), // asset -> LendingPool for that asset
+    SupportedAssets, // Vec<Address>
+    CreditPolicy,
+    RiskManager, // may restructure loans alongside the admin
+    DeferralPolicy,
+    DeferralRequest(u64), // loan_id -> timestamp of a request awaiting the admin
+    LateFeeBps,
 }
 
-#[contractevent]
+// A borrower's open loans measured against their credit policy
+#[contracttype]
+#[derive(Clone)]
+pub struct BorrowerExposure {
+    pub open_loans: u32, // pending and active
+    pub outstanding: i128, // principal owed or requested
+    pub monthly_debt: i128, // installments across open loans, scaled to a month
+    pub credit_limit: i128,
+    pub available_credit: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LoanManagerConfig {
+    pub admin: Address,
+    pub nft_contract: Address,
+    pub oracle_contract: Address,
+    pub risk_manager: Option<Address>,
+    pub default_asset: Address,
+    pub supported_assets: Vec<Address>,
+}
+
+// Pre-contract disclosure of a loan's cost, built from the same schedule `make_payment` follows
+#[contracttype]
+#[derive(Clone)]
+pub struct LoanDisclosure {
+    pub amount_financed: i128, // credit provided to the borrower, net of fees
+    pub origination_fee: i128,
+    pub finance_charge: i128, // total cost of credit
+    pub total_of_payments: i128,
+    pub apr_bps: u32, // annual percentage rate
+    pub interest_rate: u32, // note rate in basis points
+    pub frequency: PaymentFrequency,
+    pub installments: u32,
+    pub installment_amount: i128,
+    pub duration_months: u32,
+    pub schedule: Vec<ScheduledPayment>,
+}
+
+#[contractevent(topics = ["loan_requested"])]
 pub struct LoanRequestedEvent {
+    #[topic]
     pub borrower: Address,
     pub loan_id: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["loan_approved"])]
 pub struct LoanApprovedEvent {
     pub loan_id: u64,
 }
 
-#[contractevent]
+#[contractevent(topics = ["payment_made"])]
 pub struct PaymentMadeEvent {
+    #[topic]
     pub loan_id: u64,
     pub amount: i128,
 }
 
-#[contractevent]
+#[contractevent(topics = ["payment_allocated"])]
+pub struct PaymentAllocatedEvent {
+    #[topic]
+    pub loan_id: u64,
+    pub allocation: PaymentAllocation,
+}
+
+#[contractevent(topics = ["payment_missed"])]
 pub struct PaymentMissedEvent {
+    #[topic]
     pub loan_id: u64,
     pub missed_count: u32,
 }
 
+#[contractevent]
+pub struct PricingTableSet {
+    pub score_bands: u32,
+}
+
+#[contractevent]
+pub struct OriginationFee {
+    #[topic]
+    pub loan_id: u64,
+    pub fee: i128,
+}
+
+#[contractevent]
+pub struct OriginationFeeSet {
+    pub fee_bps: u32,
+}
+
+#[contractevent]
+pub struct LateFeeSet {
+    pub fee_bps: u32,
+}
+
+#[contractevent]
+pub struct PoolAdded {
+    #[topic]
+    pub asset: Address,
+    pub pool_contract: Address,
+}
+
+#[contractevent]
+pub struct PoolRemoved {
+    pub asset: Address,
+}
+
+#[contractevent]
+pub struct CreditPolicySet {
+    pub policy: CreditPolicy,
+}
+
+#[contractevent]
+pub struct LoanRestructured {
+    #[topic]
+    pub loan_id: u64,
+    pub new_duration_months: u32,
+    pub new_rate: u32,
+}
+
+#[contractevent]
+pub struct LoanRefinanced {
+    #[topic]
+    pub loan_id: u64,
+    pub new_duration_months: u32,
+    pub new_rate: u32,
+}
+
+#[contractevent]
+pub struct RiskManagerSet {
+    pub risk_manager: Option<Address>,
+}
+
+#[contractevent]
+pub struct DeferralRequested {
+    #[topic]
+    pub loan_id: u64,
+    pub score: u32,
+}
+
+#[contractevent]
+pub struct DeferralRejected {
+    pub loan_id: u64,
+}
+
+#[contractevent]
+pub struct PaymentDeferred {
+    #[topic]
+    pub loan_id: u64,
+    pub next_payment_due: u64,
+    pub interest: i128,
+}
+
+#[contractevent]
+pub struct DeferralPolicySet {
+    pub policy: DeferralPolicy,
+}
+
+#[contractevent]
+pub struct NftContractSet {
+    pub nft_contract: Address,
+}
+
+#[contractevent]
+pub struct OracleContractSet {
+    pub oracle_contract: Address,
+}
+
 #[contract]
 pub struct LoanManager;
 
 #[contractimpl]
 impl LoanManager {
-    pub fn __initialize(
-        env: Env,
-        nft_contract: Address,
-        pool_contract: Address,
-        oracle_contract: Address,
-        usdc_token: Address
-    ) {
-        env.storage().instance().set(&DataKey::RemittanceNFTContract, &nft_contract);
-        env.storage().instance().set(&DataKey::LendingPoolContract, &pool_contract);
-        env.storage().instance().set(&DataKey::OracleContract, &oracle_contract);
-        env.storage().instance().set(&DataKey::USDCTokenAddress, &usdc_token);
-        env.storage().instance().set(&DataKey::LoanCounter, &0u64);
-        env.storage()
-            .instance()
-            .set(&DataKey::AdminAddress, &env.current_contract_address());
-    }
-
-    // Public initialize function that can be called after deployment
-    pub fn initialize(
+    // Runs once at deployment. `pool_contract` becomes the pool for `usdc_token`; more assets
+    // can be added with `add_pool`.
+    pub fn __constructor(
         env: Env,
         admin: Address,
         nft_contract: Address,
@@ -110,30 +314,24 @@ impl LoanManager {
         oracle_contract: Address,
         usdc_token: Address
     ) {
-        admin.require_auth();
-
-        // Only allow initialization if not already initialized
-        let existing: Option<Address> = env
-            .storage()
-            .instance()
-            .get(&DataKey::RemittanceNFTContract);
-        assert!(existing.is_none(), "Contract already initialized");
-
         env.storage().instance().set(&DataKey::RemittanceNFTContract, &nft_contract);
         env.storage().instance().set(&DataKey::LendingPoolContract, &pool_contract);
         env.storage().instance().set(&DataKey::OracleContract, &oracle_contract);
         env.storage().instance().set(&DataKey::USDCTokenAddress, &usdc_token);
         env.storage().instance().set(&DataKey::AdminAddress, &admin);
         env.storage().instance().set(&DataKey::LoanCounter, &0u64);
+        Self::register_pool(&env, &usdc_token, &pool_contract);
     }
 
-    // Request loan
+    // Request loan, repaid in installments every week, two weeks or month over `duration_months`
     pub fn request_loan(
         env: Env,
         borrower: Address,
         nft_id: u64,
         amount: i128,
-        duration_months: u32
+        duration_months: u32,
+        asset: Address,
+        frequency: PaymentFrequency
     ) -> u64 {
         borrower.require_auth();
 
@@ -148,12 +346,37 @@ impl LoanManager {
         let nft_data = nft_client.get_nft_data(&nft_id);
         assert!(nft_data.owner == borrower, "NFT does not belong to borrower");
 
-        // Calculate loan terms
-        let interest_rate = Self::calculate_interest_rate(&env, nft_id);
-        let monthly_payment = Self::calculate_monthly_payment(
+        assert!(amount > 0, "Amount must be positive");
+        assert!(duration_months > 0, "Duration must be positive");
+
+        assert!(Self::get_supported_assets(env.clone()).contains(&asset), "Unsupported asset");
+
+        let policy = Self::get_credit_policy(env.clone());
+        let exposure = Self::get_borrower_exposure(env.clone(), borrower.clone(), nft_id);
+        assert!(exposure.open_loans < policy.max_active_loans, "Too many open loans");
+
+        // Calculate loan terms against the pool for the requested asset
+        let interest_rate = Self::calculate_interest_rate(
+            &env,
+            nft_id,
+            amount,
+            duration_months,
+            &asset
+        );
+        let installment_amount = pricing::installment_amount(
             amount,
             interest_rate,
-            duration_months
+            duration_months,
+            frequency
+        );
+        assert!(installment_amount > 0, "Amount too small for the term");
+        let origination_fee = Self::calculate_origination_fee(&env, amount);
+
+        Self::check_credit(
+            &policy,
+            &nft_data,
+            exposure.outstanding + amount,
+            exposure.monthly_debt + pricing::monthly_equivalent(installment_amount, frequency)
         );
 
         // Create loan
@@ -169,12 +392,23 @@ impl LoanManager {
             total_repaid: 0,
             interest_rate,
             duration_months,
-            monthly_payment,
+            frequency,
+            installments: frequency.installments(duration_months),
+            installment_amount,
             start_timestamp: env.ledger().timestamp(),
-            next_payment_due: env.ledger().timestamp() + 30 * 24 * 60 * 60, // 30 days
+            next_payment_due: env.ledger().timestamp() + frequency.period_seconds(),
             status: LoanStatus::Pending,
             payments_made: 0,
             payments_missed: 0,
+            origination_fee,
+            asset,
+            restructurings: Vec::new(&env),
+            deferrals_used: 0,
+            deferred_until: 0,
+            capitalized_interest: 0,
+            fees_due: 0,
+            accrued_interest: 0,
+            installment_paid: 0,
         };
 
         env.storage().instance().set(&DataKey::LoanCounter, &counter);
@@ -189,7 +423,7 @@ impl LoanManager {
         borrower_loans.push_back(counter);
         env.storage().instance().set(&DataKey::BorrowerLoans(borrower.clone()), &borrower_loans);
 
-        env.events().publish(("loan_requested", borrower), counter);
+        (LoanRequestedEvent { borrower, loan_id: counter }).publish(&env);
 
         counter
     }
@@ -211,6 +445,18 @@ impl LoanManager {
 
         assert!(loan.status == LoanStatus::Pending, "Loan not pending");
 
+        // Re-check credit against the score and loans as they stand now. Other pending requests
+        // are not counted; they are checked again when approved.
+        let policy = Self::get_credit_policy(env.clone());
+        let (_, outstanding, monthly_debt) = Self::open_loan_totals(&env, &loan.borrower, false);
+        let nft_data = Self::nft_data(&env, loan.nft_collateral_id);
+        Self::check_credit(
+            &policy,
+            &nft_data,
+            outstanding + loan.loan_amount,
+            monthly_debt + pricing::monthly_equivalent(loan.installment_amount, loan.frequency)
+        );
+
         // Stake NFT as collateral
         let nft_contract: Address = env
             .storage()
@@ -220,27 +466,38 @@ impl LoanManager {
         let nft_client = nft::Client::new(&env, &nft_contract);
         nft_client.stake_nft(&loan.nft_collateral_id, &loan_id);
 
-        // Borrow funds from the lending pool and disburse to borrower
-        let pool_contract: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::LendingPoolContract)
-            .unwrap();
+        // Borrow funds from the loan asset's pool and disburse to borrower
+        let pool_contract = Self::get_pool(env.clone(), loan.asset.clone());
         let pool_client = pool::Client::new(&env, &pool_contract);
-        pool_client.borrow(&loan.loan_amount, &loan.borrower, &loan_id);
+        pool_client.borrow(&loan.loan_amount, &loan.origination_fee, &loan.borrower, &loan_id);
+        if loan.origination_fee > 0 {
+            (OriginationFee { loan_id, fee: loan.origination_fee }).publish(&env);
+        }
 
         // Update loan status and payment schedule
         loan.status = LoanStatus::Active;
         loan.start_timestamp = env.ledger().timestamp();
-        loan.next_payment_due = env.ledger().timestamp() + 30 * 24 * 60 * 60;
+        loan.next_payment_due = env.ledger().timestamp() + loan.frequency.period_seconds();
+        loan.accrued_interest = pricing::interest_portion(
+            loan.loan_amount,
+            loan.interest_rate,
+            loan.frequency
+        );
 
         env.storage().instance().set(&DataKey::Loan(loan_id), &loan);
 
-        env.events().publish(("loan_approved",), loan_id);
+        (LoanApprovedEvent { loan_id }).publish(&env);
     }
 
-    // Process payment
-    pub fn make_payment(env: Env, loan_id: u64, amount: i128) {
+    // Process payment through the waterfall: late fees, accrued interest, the installment's
+    // principal, then any excess as the borrower chooses. A cycle only counts as paid once its
+    // whole installment is covered.
+    pub fn make_payment(
+        env: Env,
+        loan_id: u64,
+        amount: i128,
+        excess: ExcessPayment
+    ) -> PaymentAllocation {
         let mut loan: Loan = env
             .storage()
             .instance()
@@ -252,110 +509,294 @@ impl LoanManager {
 
         loan.borrower.require_auth();
 
-        // Transfer USDC from borrower to pool
-        let usdc_token: Address = env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap();
-        let pool_contract: Address = env
+        assert!(amount <= repayment::total_owed(&loan), "Payment exceeds amount owed");
+        let allocation = repayment::allocate(&mut loan, amount, excess);
+        Self::collect(&env, loan_id, &mut loan, amount, &allocation);
+
+        allocation
+    }
+
+    // Process automatic repayment (called by Oracle)
+    // A remittance sent at `remitted_at` pays every installment due by then (at least the next
+    // one), one cycle at a time. The installments are collected in a single transfer.
+    pub fn process_automatic_repayment(
+        env: Env,
+        loan_id: u64,
+        remittance_amount: i128,
+        remitted_at: u64
+    ) -> i128 {
+        let oracle: Address = env.storage().instance().get(&DataKey::OracleContract).unwrap();
+        oracle.require_auth();
+        assert!(remitted_at <= env.ledger().timestamp(), "Remittance in the future");
+
+        let mut loan = Self::get_loan(env.clone(), loan_id);
+        assert!(loan.status == LoanStatus::Active, "Loan not active");
+        assert!(remitted_at >= loan.start_timestamp, "Remittance before loan start");
+        loan.borrower.require_auth();
+
+        let cycles = Self::get_cycles_due(env.clone(), loan_id, remitted_at);
+        let mut remaining = remittance_amount;
+        let mut allocation = PaymentAllocation::default();
+        for _ in 0..cycles {
+            if repayment::total_owed(&loan) <= 0 || remaining <= 0 {
+                break;
+            }
+
+            let installment = repayment::installment_remaining(&loan);
+            let payment_amount = if remaining >= installment { installment } else { remaining };
+
+            let cycle = repayment::allocate(&mut loan, payment_amount, ExcessPayment::PayAhead);
+            allocation.add(&cycle);
+            remaining -= payment_amount;
+        }
+
+        let paid = remittance_amount - remaining;
+        if paid > 0 {
+            Self::collect(&env, loan_id, &mut loan, paid, &allocation);
+        }
+
+        // Return remaining amount for recipient
+        remaining
+    }
+
+    // Installments falling due by `timestamp` and still unpaid, counting the next one even
+    // before its due date
+    pub fn get_cycles_due(env: Env, loan_id: u64, timestamp: u64) -> u32 {
+        let loan = Self::get_loan(env, loan_id);
+        if timestamp < loan.next_payment_due {
+            return 1;
+        }
+        1 + ((timestamp - loan.next_payment_due) / loan.frequency.period_seconds()) as u32
+    }
+
+    // Late fees plus what is left of the current installment, capped at what is owed so the
+    // last one clears the loan
+    pub fn get_installment_due(env: Env, loan_id: u64) -> i128 {
+        repayment::installment_remaining(&Self::get_loan(env, loan_id))
+    }
+
+    // Fees, accrued interest and principal still owed
+    pub fn get_payoff_amount(env: Env, loan_id: u64) -> i128 {
+        repayment::total_owed(&Self::get_loan(env, loan_id))
+    }
+
+    // Mark payment as missed (called by Oracle)
+    pub fn mark_payment_missed(env: Env, loan_id: u64) {
+        let oracle: Address = env.storage().instance().get(&DataKey::OracleContract).unwrap();
+        oracle.require_auth();
+
+        let mut loan: Loan = env
             .storage()
             .instance()
-            .get(&DataKey::LendingPoolContract)
-            .unwrap();
-
-        let usdc_client = token::Client::new(&env, &usdc_token);
-        usdc_client.transfer(&loan.borrower, &pool_contract, &amount);
+            .get(&DataKey::Loan(loan_id))
+            .expect("Loan does not exist");
+        assert!(env.ledger().timestamp() >= loan.deferred_until, "Payment deferred");
+        assert!(env.ledger().timestamp() > loan.next_payment_due, "Payment not yet due");
 
-        // Calculate principal and interest split
-        let interest_portion = Self::calculate_interest_portion(
+        // The missed installment's cycle is over: charge the late fee and start the next cycle.
+        // Unpaid interest stays accrued on top of the new cycle's.
+        loan.payments_missed += 1;
+        let late_fee_bps = Self::get_late_fee(env.clone()) as i128;
+        loan.fees_due += (loan.installment_amount * late_fee_bps) / 10000;
+        loan.installment_paid = 0;
+        loan.next_payment_due += loan.frequency.period_seconds();
+        loan.accrued_interest += pricing::interest_portion(
             loan.outstanding_balance,
-            loan.interest_rate
+            loan.interest_rate,
+            loan.frequency
         );
-        let principal_portion = if amount > interest_portion {
-            amount - interest_portion
-        } else {
-            0
-        };
 
-        // Update loan
-        loan.total_repaid += amount;
-        loan.outstanding_balance -= principal_portion;
-        loan.payments_made += 1;
-        loan.next_payment_due += 30 * 24 * 60 * 60; // Next month
-
-        // Check if fully repaid
-        if loan.outstanding_balance <= 0 {
-            loan.status = LoanStatus::Repaid;
+        // Check for default (2 consecutive missed payments)
+        if loan.payments_missed >= credit::MISSES_TO_DEFAULT && loan.status == LoanStatus::Active {
+            loan.status = LoanStatus::Defaulted;
 
-            // Unstake NFT
-            let _nft_contract: Address = env
-                .storage()
-                .instance()
-                .get(&DataKey::RemittanceNFTContract)
-                .unwrap();
-            // Call _nft_contract.unstake_nft(loan.nft_collateral_id)
-            let nft_client = nft::Client::new(&env, &_nft_contract);
-            nft_client.unstake_nft(&loan.nft_collateral_id);
+            // Let the pool absorb the unpaid principal, insurance reserve first
+            let pool_contract = Self::get_pool(env.clone(), loan.asset.clone());
+            let pool_client = pool::Client::new(&env, &pool_contract);
+            pool_client.write_off(
+                &loan_id,
+                &(loan.outstanding_balance - loan.capitalized_interest)
+            );
         }
 
-        // Notify pool of repayment
-        let pool_contract: Address = env
+        env.storage().instance().set(&DataKey::Loan(loan_id), &loan);
+
+        (PaymentMissedEvent { loan_id, missed_count: loan.payments_missed }).publish(&env);
+    }
+
+    // Re-amortize an active loan's outstanding balance over `new_duration_months` at
+    // `new_rate`, starting a month from now. Missed payments are forgiven so a borrower in
+    // hardship is not pushed into default. Admin or risk manager only.
+    pub fn restructure_loan(
+        env: Env,
+        caller: Address,
+        loan_id: u64,
+        new_duration_months: u32,
+        new_rate: u32
+    ) {
+        caller.require_auth();
+        let admin: Address = env
             .storage()
             .instance()
-            .get(&DataKey::LendingPoolContract)
-            .unwrap();
-        let pool_client = pool::Client::new(&env, &pool_contract);
-        pool_client.repay(&principal_portion, &interest_portion, &loan_id);
-        // Use existing `pool_contract` variable above for notifications.
-        // Call pool_contract.repay(principal_portion, interest_portion, loan_id)
+            .get(&DataKey::AdminAddress)
+            .expect("Admin not configured");
+        assert!(
+            caller == admin || Some(caller.clone()) == Self::get_risk_manager(env.clone()),
+            "Not authorized to restructure"
+        );
+
+        let mut loan: Loan = env
+            .storage()
+            .instance()
+            .get(&DataKey::Loan(loan_id))
+            .expect("Loan does not exist");
+        assert!(loan.status == LoanStatus::Active, "Loan not active");
+        assert!(new_duration_months > 0, "Duration must be positive");
+        assert!(
+            new_rate <= Self::get_pricing_table(env.clone()).max_rate_bps,
+            "Rate above pricing cap"
+        );
 
+        Self::reamortize(
+            &env,
+            &mut loan,
+            RestructuringKind::Restructured,
+            new_duration_months,
+            new_rate
+        );
         env.storage().instance().set(&DataKey::Loan(loan_id), &loan);
 
-        env.events().publish(("payment_made", loan_id), amount);
+        (LoanRestructured { loan_id, new_duration_months, new_rate }).publish(&env);
     }
 
-    // Process automatic repayment (called by Oracle)
-    pub fn process_automatic_repayment(env: Env, loan_id: u64, remittance_amount: i128) -> i128 {
-        let oracle: Address = env.storage().instance().get(&DataKey::OracleContract).unwrap();
+    // Reprice an active loan in good standing at the rate the borrower's current NFT score
+    // earns, over `new_duration_months`. Only allowed when the new rate is lower and the new
+    // installment still fits the credit policy.
+    pub fn refinance(env: Env, loan_id: u64, new_duration_months: u32) -> u32 {
+        let mut loan: Loan = env
+            .storage()
+            .instance()
+            .get(&DataKey::Loan(loan_id))
+            .expect("Loan does not exist");
+
+        loan.borrower.require_auth();
+        assert!(loan.status == LoanStatus::Active, "Loan not active");
+        assert!(loan.payments_missed == 0, "Loan has missed payments");
+        assert!(new_duration_months > 0, "Duration must be positive");
 
+        let new_rate = Self::calculate_interest_rate(
+            &env,
+            loan.nft_collateral_id,
+            loan.outstanding_balance,
+            new_duration_months,
+            &loan.asset
+        );
+        assert!(new_rate < loan.interest_rate, "No cheaper rate available");
+
+        // The balance is unchanged; only the installment moves
+        let policy = Self::get_credit_policy(env.clone());
+        let (_, outstanding, monthly_debt) = Self::open_loan_totals(&env, &loan.borrower, false);
+        let new_installment = pricing::installment_amount(
+            loan.outstanding_balance,
+            new_rate,
+            new_duration_months,
+            loan.frequency
+        );
+        Self::check_credit(
+            &policy,
+            &Self::nft_data(&env, loan.nft_collateral_id),
+            outstanding,
+            monthly_debt -
+                pricing::monthly_equivalent(loan.installment_amount, loan.frequency) +
+                pricing::monthly_equivalent(new_installment, loan.frequency)
+        );
+
+        Self::reamortize(
+            &env,
+            &mut loan,
+            RestructuringKind::Refinanced,
+            new_duration_months,
+            new_rate
+        );
+        env.storage().instance().set(&DataKey::Loan(loan_id), &loan);
+
+        (LoanRefinanced { loan_id, new_duration_months, new_rate }).publish(&env);
+        new_rate
+    }
+
+    // Ask to skip the next installment. Granted at once when the borrower's score meets the
+    // deferral policy; otherwise it waits for `approve_deferral`. Returns whether it was granted.
+    pub fn request_deferral(env: Env, loan_id: u64) -> bool {
         let loan: Loan = env
             .storage()
             .instance()
             .get(&DataKey::Loan(loan_id))
             .expect("Loan does not exist");
 
-        let payment_amount = if remittance_amount >= loan.monthly_payment {
-            loan.monthly_payment
-        } else {
-            remittance_amount
-        };
+        loan.borrower.require_auth();
+        assert!(loan.status == LoanStatus::Active, "Loan not active");
+        assert!(env.ledger().timestamp() <= loan.next_payment_due, "Payment already overdue");
+        assert!(
+            !env.storage().instance().has(&DataKey::DeferralRequest(loan_id)),
+            "Deferral already requested"
+        );
 
-        // Process payment
-        Self::make_payment(env.clone(), loan_id, payment_amount);
+        let policy = Self::get_deferral_policy(env.clone());
+        assert!(loan.deferrals_used < policy.max_per_loan, "No deferrals left");
 
-        // Return remaining amount for recipient
-        remittance_amount - payment_amount
+        let score = Self::nft_data(&env, loan.nft_collateral_id).reliability_score;
+        if score >= policy.auto_approve_min_score {
+            Self::defer(&env, loan, &policy);
+            return true;
+        }
+
+        env.storage().instance().set(&DataKey::DeferralRequest(loan_id), &env.ledger().timestamp());
+        (DeferralRequested { loan_id, score }).publish(&env);
+        false
     }
 
-    // Mark payment as missed (called by Oracle)
-    pub fn mark_payment_missed(env: Env, loan_id: u64) {
-        let oracle: Address = env.storage().instance().get(&DataKey::OracleContract).unwrap();
-        oracle.require_auth();
+    // Grant a pending deferral request (admin only)
+    pub fn approve_deferral(env: Env, loan_id: u64) {
+        Self::require_admin(&env);
+        assert!(
+            env.storage().instance().has(&DataKey::DeferralRequest(loan_id)),
+            "No deferral requested"
+        );
+        env.storage().instance().remove(&DataKey::DeferralRequest(loan_id));
 
-        let mut loan: Loan = env
+        let loan: Loan = env
             .storage()
             .instance()
             .get(&DataKey::Loan(loan_id))
             .expect("Loan does not exist");
+        assert!(loan.status == LoanStatus::Active, "Loan not active");
+        assert!(env.ledger().timestamp() <= loan.next_payment_due, "Payment already overdue");
 
-        loan.payments_missed += 1;
+        let policy = Self::get_deferral_policy(env.clone());
+        assert!(loan.deferrals_used < policy.max_per_loan, "No deferrals left");
+        Self::defer(&env, loan, &policy);
+    }
 
-        // Check for default (2 consecutive missed payments)
-        if loan.payments_missed >= 2 {
-            loan.status = LoanStatus::Defaulted;
-            // Liquidation logic would go here
-        }
+    // Turn down a pending deferral request (admin only)
+    pub fn reject_deferral(env: Env, loan_id: u64) {
+        Self::require_admin(&env);
+        assert!(
+            env.storage().instance().has(&DataKey::DeferralRequest(loan_id)),
+            "No deferral requested"
+        );
+        env.storage().instance().remove(&DataKey::DeferralRequest(loan_id));
+        (DeferralRejected { loan_id }).publish(&env);
+    }
 
-        env.storage().instance().set(&DataKey::Loan(loan_id), &loan);
+    // When a pending deferral request for the loan was made, if any
+    pub fn get_deferral_request(env: Env, loan_id: u64) -> Option<u64> {
+        env.storage().instance().get(&DataKey::DeferralRequest(loan_id))
+    }
 
-        env.events().publish(("payment_missed", loan_id), loan.payments_missed);
+    // Whether the loan is inside a granted payment holiday, so a miss must not be reported
+    pub fn is_payment_deferred(env: Env, loan_id: u64) -> bool {
+        let loan = Self::get_loan(env.clone(), loan_id);
+        env.ledger().timestamp() < loan.deferred_until
     }
 
     // Get loan details
@@ -363,47 +804,488 @@ impl LoanManager {
         env.storage().instance().get(&DataKey::Loan(loan_id)).expect("Loan does not exist")
     }
 
-    fn calculate_interest_rate(env: &Env, nft_id: u64) -> u32 {
-        // Keep a reference to the NFT contract for potential future calls; underscore to silence unused variable warnings.
-        let _nft_contract: Address = env
-            .storage()
+    // All loans a borrower has requested, oldest first
+    pub fn get_borrower_loans(env: Env, borrower: Address) -> Vec<u64> {
+        env.storage()
             .instance()
-            .get(&DataKey::RemittanceNFTContract)
-            .unwrap();
-        let nft_client = nft::Client::new(&env, &_nft_contract);
+            .get(&DataKey::BorrowerLoans(borrower))
+            .unwrap_or(Vec::new(&env))
+    }
 
-        // Call NFT contract to get NFT data
-        let nft_data = nft_client.get_nft_data(&nft_id);
+    // Preview the terms a borrower would get before committing
+    pub fn quote_loan(
+        env: Env,
+        nft_id: u64,
+        amount: i128,
+        duration_months: u32,
+        asset: Address,
+        frequency: PaymentFrequency
+    ) -> LoanQuote {
+        assert!(amount > 0, "Amount must be positive");
+        assert!(duration_months > 0, "Duration must be positive");
+
+        let interest_rate = Self::calculate_interest_rate(
+            &env,
+            nft_id,
+            amount,
+            duration_months,
+            &asset
+        );
+        let origination_fee = Self::calculate_origination_fee(&env, amount);
+        pricing::quote(&env, amount, origination_fee, interest_rate, duration_months, frequency)
+    }
 
-        // Placeholder scoring logic using nft_id
-        let score = (nft_data.reliability_score % 100) as u32;
-        if score >= 90 {
-            1500u32
-        } else if score >= 80 {
-            2000u32
-        } else if score >= 70 {
-            3000u32
+    // Read-only disclosure of APR, finance charge and payment schedule for a prospective loan
+    pub fn disclosure(
+        env: Env,
+        nft_id: u64,
+        amount: i128,
+        duration_months: u32,
+        asset: Address,
+        frequency: PaymentFrequency
+    ) -> LoanDisclosure {
+        assert!(amount > 0, "Amount must be positive");
+        assert!(duration_months > 0, "Duration must be positive");
+
+        let interest_rate = Self::calculate_interest_rate(
+            &env,
+            nft_id,
+            amount,
+            duration_months,
+            &asset
+        );
+        let schedule = pricing::schedule(
+            &env,
+            amount,
+            interest_rate,
+            duration_months,
+            frequency,
+            env.ledger().timestamp()
+        );
+        let total_of_payments = pricing::total_of_payments(&schedule);
+        let origination_fee = Self::calculate_origination_fee(&env, amount);
+        let amount_financed = amount - origination_fee;
+
+        LoanDisclosure {
+            amount_financed,
+            origination_fee,
+            finance_charge: total_of_payments - amount_financed,
+            total_of_payments,
+            apr_bps: pricing::apr_bps(amount_financed, &schedule, frequency),
+            interest_rate,
+            frequency,
+            installments: frequency.installments(duration_months),
+            installment_amount: pricing::installment_amount(
+                amount,
+                interest_rate,
+                duration_months,
+                frequency
+            ),
+            duration_months,
+            schedule,
+        }
+    }
+
+    // Open loans, credit limit and headroom for a borrower pledging `nft_id`
+    pub fn get_borrower_exposure(env: Env, borrower: Address, nft_id: u64) -> BorrowerExposure {
+        let policy = Self::get_credit_policy(env.clone());
+        let nft_data = Self::nft_data(&env, nft_id);
+        let (open_loans, outstanding, monthly_debt) = Self::open_loan_totals(&env, &borrower, true);
+
+        let credit_limit = credit::credit_limit(
+            &policy,
+            nft_data.monthly_amount,
+            nft_data.reliability_score
+        );
+        let available_credit = if credit_limit > outstanding { credit_limit - outstanding } else { 0 };
+
+        BorrowerExposure {
+            open_loans,
+            outstanding,
+            monthly_debt,
+            credit_limit,
+            available_credit,
+        }
+    }
+
+    // Replace the borrower credit policy (admin only)
+    pub fn set_credit_policy(env: Env, policy: CreditPolicy) {
+        Self::require_admin(&env);
+        credit::validate_policy(&policy);
+
+        env.storage().instance().set(&DataKey::CreditPolicy, &policy);
+        (CreditPolicySet { policy }).publish(&env);
+    }
+
+    pub fn get_credit_policy(env: Env) -> CreditPolicy {
+        env.storage()
+            .instance()
+            .get(&DataKey::CreditPolicy)
+            .unwrap_or_else(credit::default_policy)
+    }
+
+    // Replace the payment deferral policy (admin only)
+    pub fn set_deferral_policy(env: Env, policy: DeferralPolicy) {
+        Self::require_admin(&env);
+        deferral::validate_policy(&policy);
+
+        env.storage().instance().set(&DataKey::DeferralPolicy, &policy);
+        (DeferralPolicySet { policy }).publish(&env);
+    }
+
+    pub fn get_deferral_policy(env: Env) -> DeferralPolicy {
+        env.storage()
+            .instance()
+            .get(&DataKey::DeferralPolicy)
+            .unwrap_or_else(deferral::default_policy)
+    }
+
+    // Replace the risk-based pricing table (admin only)
+    pub fn set_pricing_table(env: Env, table: PricingTable) {
+        Self::require_admin(&env);
+        pricing::validate_table(&table);
+
+        env.storage().instance().set(&DataKey::PricingTable, &table);
+        (PricingTableSet { score_bands: table.score_bands.len() }).publish(&env);
+    }
+
+    pub fn get_pricing_table(env: Env) -> PricingTable {
+        env.storage()
+            .instance()
+            .get(&DataKey::PricingTable)
+            .unwrap_or_else(|| pricing::default_table(&env))
+    }
+
+    // Origination fee charged on new loans, in basis points of the principal (admin only)
+    pub fn set_origination_fee(env: Env, fee_bps: u32) {
+        Self::require_admin(&env);
+        assert!(fee_bps < 10000, "Fee too high");
+
+        env.storage().instance().set(&DataKey::OriginationFeeBps, &fee_bps);
+        (OriginationFeeSet { fee_bps }).publish(&env);
+    }
+
+    pub fn get_origination_fee(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::OriginationFeeBps).unwrap_or(0)
+    }
+
+    // Fee charged when an installment is missed, in basis points of the installment (admin only)
+    pub fn set_late_fee(env: Env, fee_bps: u32) {
+        Self::require_admin(&env);
+        assert!(fee_bps <= 2000, "Fee too high");
+
+        env.storage().instance().set(&DataKey::LateFeeBps, &fee_bps);
+        (LateFeeSet { fee_bps }).publish(&env);
+    }
+
+    pub fn get_late_fee(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::LateFeeBps).unwrap_or(0)
+    }
+
+    // Repoint collateral checks and staking at a new RemittanceNFT (admin only)
+    pub fn set_nft_contract(env: Env, nft_contract: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::RemittanceNFTContract, &nft_contract);
+        (NftContractSet { nft_contract }).publish(&env);
+    }
+
+    // Change which contract may report missed payments and trigger repayments (admin only)
+    pub fn set_oracle_contract(env: Env, oracle_contract: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::OracleContract, &oracle_contract);
+        (OracleContractSet { oracle_contract }).publish(&env);
+    }
+
+    // Let a risk manager restructure loans alongside the admin, or remove them with `None`
+    // (admin only)
+    pub fn set_risk_manager(env: Env, risk_manager: Option<Address>) {
+        Self::require_admin(&env);
+
+        match &risk_manager {
+            Some(address) => env.storage().instance().set(&DataKey::RiskManager, address),
+            None => env.storage().instance().remove(&DataKey::RiskManager),
+        }
+        (RiskManagerSet { risk_manager }).publish(&env);
+    }
+
+    pub fn get_risk_manager(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::RiskManager)
+    }
+
+    // Pools are listed per asset; see `get_pool`
+    pub fn get_config(env: Env) -> LoanManagerConfig {
+        LoanManagerConfig {
+            admin: env.storage().instance().get(&DataKey::AdminAddress).expect("Admin not configured"),
+            nft_contract: env.storage().instance().get(&DataKey::RemittanceNFTContract).unwrap(),
+            oracle_contract: env.storage().instance().get(&DataKey::OracleContract).unwrap(),
+            risk_manager: Self::get_risk_manager(env.clone()),
+            default_asset: env.storage().instance().get(&DataKey::USDCTokenAddress).unwrap(),
+            supported_assets: Self::get_supported_assets(env.clone()),
+        }
+    }
+
+    // Register or replace the lending pool for an asset (admin only)
+    pub fn add_pool(env: Env, asset: Address, pool_contract: Address) {
+        Self::require_admin(&env);
+
+        let pool_client = pool::Client::new(&env, &pool_contract);
+        assert!(pool_client.get_asset() == asset, "Pool asset mismatch");
+
+        Self::register_pool(&env, &asset, &pool_contract);
+        if Some(asset.clone()) == env.storage().instance().get(&DataKey::USDCTokenAddress) {
+            env.storage().instance().set(&DataKey::LendingPoolContract, &pool_contract);
+        }
+        (PoolAdded { asset, pool_contract }).publish(&env);
+    }
+
+    // Stop new loans in an asset (admin only). The pool stays registered so existing loans
+    // can still be repaid or written off against it.
+    pub fn remove_pool(env: Env, asset: Address) {
+        Self::require_admin(&env);
+
+        let mut assets = Self::get_supported_assets(env.clone());
+        let index = assets.first_index_of(&asset).expect("Unsupported asset");
+        assets.remove(index);
+
+        env.storage().instance().set(&DataKey::SupportedAssets, &assets);
+        (PoolRemoved { asset }).publish(&env);
+    }
+
+    pub fn get_pool(env: Env, asset: Address) -> Address {
+        env.storage().instance().get(&DataKey::AssetPool(asset)).expect("Unsupported asset")
+    }
+
+    pub fn get_supported_assets(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::SupportedAssets)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Internal: Record the pool serving an asset
+    fn register_pool(env: &Env, asset: &Address, pool_contract: &Address) {
+        let mut assets = Self::get_supported_assets(env.clone());
+        if !assets.contains(asset) {
+            assets.push_back(asset.clone());
+            env.storage().instance().set(&DataKey::SupportedAssets, &assets);
+        }
+        env.storage().instance().set(&DataKey::AssetPool(asset.clone()), pool_contract);
+    }
+
+    // Internal: Move `amount` from the borrower to the loan's pool and credit the pool with what
+    // `allocation` says it covers
+    fn collect(
+        env: &Env,
+        loan_id: u64,
+        loan: &mut Loan,
+        amount: i128,
+        allocation: &PaymentAllocation
+    ) {
+        // Capitalized interest is repaid first and reaches the pool as interest, since the pool
+        // never lent it. Fees are income to the pool as well.
+        let principal = allocation.principal();
+        let capitalized_repaid = if principal < loan.capitalized_interest {
+            principal
         } else {
-            4000u32
+            loan.capitalized_interest
+        };
+        loan.capitalized_interest -= capitalized_repaid;
+
+        // Transfer the loan asset from borrower to its pool
+        let pool_contract = Self::get_pool(env.clone(), loan.asset.clone());
+
+        let asset_client = token::Client::new(env, &loan.asset);
+        asset_client.transfer(&loan.borrower, &pool_contract, &amount);
+
+        loan.total_repaid += amount;
+
+        // Check if fully repaid
+        if repayment::total_owed(loan) <= 0 {
+            loan.status = LoanStatus::Repaid;
+
+            // Unstake NFT
+            let nft_contract: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::RemittanceNFTContract)
+                .unwrap();
+            let nft_client = nft::Client::new(env, &nft_contract);
+            nft_client.unstake_nft(&loan.nft_collateral_id);
         }
+
+        // Notify pool of repayment
+        let pool_client = pool::Client::new(env, &pool_contract);
+        pool_client.repay(
+            &(principal - capitalized_repaid),
+            &(allocation.fees + allocation.interest + capitalized_repaid),
+            &loan_id
+        );
+
+        env.storage().instance().set(&DataKey::Loan(loan_id), loan);
+
+        (PaymentMadeEvent { loan_id, amount }).publish(env);
+        (PaymentAllocatedEvent { loan_id, allocation: allocation.clone() }).publish(env);
+    }
+
+    // Internal: Push the next installment back a cycle, capitalizing or waiving its interest.
+    // An installment that is already overdue can no longer be deferred.
+    fn defer(env: &Env, mut loan: Loan, policy: &DeferralPolicy) {
+        assert!(env.ledger().timestamp() <= loan.next_payment_due, "Payment already overdue");
+
+        let interest = pricing::interest_portion(
+            loan.outstanding_balance,
+            loan.interest_rate,
+            loan.frequency
+        );
+        if policy.interest == DeferredInterest::Capitalize {
+            loan.outstanding_balance += interest;
+            loan.capitalized_interest += interest;
+        }
+
+        loan.deferrals_used += 1;
+        loan.next_payment_due += loan.frequency.period_seconds();
+        loan.deferred_until = loan.next_payment_due;
+        env.storage().instance().set(&DataKey::Loan(loan.loan_id), &loan);
+
+        (PaymentDeferred {
+            loan_id: loan.loan_id,
+            next_payment_due: loan.next_payment_due,
+            interest,
+        }).publish(env);
+    }
+
+    // Internal: Put a loan on new terms for its outstanding balance and record the change
+    fn reamortize(
+        env: &Env,
+        loan: &mut Loan,
+        kind: RestructuringKind,
+        new_duration_months: u32,
+        new_rate: u32
+    ) {
+        let new_installment = pricing::installment_amount(
+            loan.outstanding_balance,
+            new_rate,
+            new_duration_months,
+            loan.frequency
+        );
+
+        loan.restructurings.push_back(Restructuring {
+            kind,
+            timestamp: env.ledger().timestamp(),
+            outstanding_balance: loan.outstanding_balance,
+            previous_rate: loan.interest_rate,
+            new_rate,
+            previous_duration_months: loan.duration_months,
+            new_duration_months,
+            previous_installment_amount: loan.installment_amount,
+            new_installment_amount: new_installment,
+            payments_missed: loan.payments_missed,
+        });
+
+        loan.interest_rate = new_rate;
+        loan.duration_months = new_duration_months;
+        loan.installments = loan.frequency.installments(new_duration_months);
+        loan.installment_amount = new_installment;
+        loan.installment_paid = 0;
+        loan.payments_missed = 0;
+        loan.next_payment_due = env.ledger().timestamp() + loan.frequency.period_seconds();
+    }
+
+    // Internal: Assert a borrower's total principal and installments fit their credit policy
+    fn check_credit(
+        policy: &CreditPolicy,
+        nft_data: &nft::RemittanceData,
+        outstanding: i128,
+        monthly_debt: i128
+    ) {
+        let limit = credit::credit_limit(
+            policy,
+            nft_data.monthly_amount,
+            nft_data.reliability_score
+        );
+        assert!(outstanding <= limit, "Credit limit exceeded");
+        assert!(
+            credit::debt_to_income_bps(monthly_debt, nft_data.monthly_amount) <=
+                (policy.max_debt_to_income_bps as i128),
+            "Debt-to-income ratio too high"
+        );
+    }
+
+    // Internal: Count, outstanding principal and installments of a borrower's active loans,
+    // plus pending ones when `include_pending`
+    fn open_loan_totals(env: &Env, borrower: &Address, include_pending: bool) -> (u32, i128, i128) {
+        let loan_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::BorrowerLoans(borrower.clone()))
+            .unwrap_or(Vec::new(env));
+
+        let mut count = 0u32;
+        let mut outstanding: i128 = 0;
+        let mut monthly_debt: i128 = 0;
+        for loan_id in loan_ids.iter() {
+            let loan: Loan = env.storage().instance().get(&DataKey::Loan(loan_id)).unwrap();
+            let open =
+                loan.status == LoanStatus::Active ||
+                (include_pending && loan.status == LoanStatus::Pending);
+            if open {
+                count += 1;
+                outstanding += loan.outstanding_balance;
+                monthly_debt += pricing::monthly_equivalent(
+                    loan.installment_amount,
+                    loan.frequency
+                );
+            }
+        }
+        (count, outstanding, monthly_debt)
     }
 
-    // Internal: Calculate monthly payment
-    fn calculate_monthly_payment(principal: i128, annual_rate_bps: u32, months: u32) -> i128 {
-        // Monthly rate = annual rate / 12
-        let monthly_rate_bps = annual_rate_bps / 12;
+    fn nft_data(env: &Env, nft_id: u64) -> nft::RemittanceData {
+        let nft_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RemittanceNFTContract)
+            .unwrap();
+        nft::Client::new(env, &nft_contract).get_nft_data(&nft_id)
+    }
 
-        // Simple interest calculation for MVP
-        let total_interest =
-            (principal * (annual_rate_bps as i128) * (months as i128)) / (12 * 10000);
-        let total_repayment = principal + total_interest;
+    // Internal: Require auth from the stored admin
+    fn require_admin(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminAddress)
+            .expect("Admin not configured");
+        admin.require_auth();
+    }
 
-        total_repayment / (months as i128)
+    // Internal: Fee withheld from a loan's disbursement
+    fn calculate_origination_fee(env: &Env, amount: i128) -> i128 {
+        let fee_bps = Self::get_origination_fee(env.clone());
+        (amount * (fee_bps as i128)) / 10000
     }
 
-    // Internal: Calculate interest portion of payment
-    fn calculate_interest_portion(outstanding: i128, annual_rate_bps: u32) -> i128 {
-        let monthly_rate_bps = annual_rate_bps / 12;
-        (outstanding * (monthly_rate_bps as i128)) / 10000
+    // Internal: Price a loan from the borrower's NFT score and current pool utilization
+    fn calculate_interest_rate(
+        env: &Env,
+        nft_id: u64,
+        amount: i128,
+        duration_months: u32,
+        asset: &Address
+    ) -> u32 {
+        let nft_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RemittanceNFTContract)
+            .unwrap();
+        let nft_client = nft::Client::new(env, &nft_contract);
+        let score = nft_client.get_nft_data(&nft_id).reliability_score;
+
+        let pool_contract = Self::get_pool(env.clone(), asset.clone());
+        let pool_client = pool::Client::new(env, &pool_contract);
+        let utilization = pool_client.get_utilization_rate();
+
+        let table = Self::get_pricing_table(env.clone());
+        pricing::rate_for(&table, score, duration_months, amount, utilization)
     }
 }
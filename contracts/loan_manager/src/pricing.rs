@@ -0,0 +1,282 @@
+use soroban_sdk::{ contracttype, Env, Vec };
+
+use remitlend_math::pricing as math;
+
+// Longest schedule `apr_bps` can price (ten years of weekly installments); the payments are
+// copied into a fixed buffer because the solver walks them many times
+const MAX_SCHEDULE_LEN: usize = 520;
+
+// How often installments fall due
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaymentFrequency {
+    Weekly = 0,
+    BiWeekly = 1,
+    Monthly = 2,
+}
+
+impl PaymentFrequency {
+    pub fn period_seconds(self) -> u64 {
+        match self {
+            PaymentFrequency::Weekly => 7 * math::SECONDS_PER_DAY,
+            PaymentFrequency::BiWeekly => 14 * math::SECONDS_PER_DAY,
+            PaymentFrequency::Monthly => math::SECONDS_PER_MONTH,
+        }
+    }
+
+    pub fn periods_per_year(self) -> u32 {
+        match self {
+            PaymentFrequency::Weekly => 52,
+            PaymentFrequency::BiWeekly => 26,
+            PaymentFrequency::Monthly => 12,
+        }
+    }
+
+    // Installments in a term of `months`
+    pub fn installments(self, months: u32) -> u32 {
+        math::installments_for(months, self.periods_per_year())
+    }
+}
+
+// Flat installment for a term of `months` paid at `frequency`
+pub fn installment_amount(
+    principal: i128,
+    annual_rate_bps: u32,
+    months: u32,
+    frequency: PaymentFrequency
+) -> i128 {
+    math::installment(
+        principal,
+        annual_rate_bps,
+        frequency.installments(months),
+        frequency.periods_per_year()
+    )
+}
+
+// Interest accrued on the outstanding balance for one cycle
+pub fn interest_portion(
+    outstanding: i128,
+    annual_rate_bps: u32,
+    frequency: PaymentFrequency
+) -> i128 {
+    math::interest_portion(outstanding, annual_rate_bps, frequency.periods_per_year())
+}
+
+// Installment scaled to a month, for debt-to-income checks against monthly remittances
+pub fn monthly_equivalent(installment: i128, frequency: PaymentFrequency) -> i128 {
+    math::monthly_equivalent(installment, frequency.periods_per_year())
+}
+
+// Base rate for borrowers whose score is at least `min_score`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreBand {
+    pub min_score: u32,
+    pub rate_bps: u32,
+}
+
+// Premium added for loans of at least `min_months`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DurationBand {
+    pub min_months: u32,
+    pub premium_bps: u32,
+}
+
+// Premium added for loans of at least `min_amount`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeBand {
+    pub min_amount: i128,
+    pub premium_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PricingTable {
+    pub score_bands: Vec<ScoreBand>, // ascending by min_score, first band starts at 0
+    pub duration_bands: Vec<DurationBand>, // ascending by min_months
+    pub size_bands: Vec<SizeBand>, // ascending by min_amount
+    pub utilization_kink_bps: u32, // pool utilization where the premium starts
+    pub utilization_premium_bps: u32, // premium at 100% utilization, linear above the kink
+    pub max_rate_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoanQuote {
+    pub interest_rate: u32, // APR in basis points used for the schedule
+    pub origination_fee: i128,
+    pub frequency: PaymentFrequency,
+    pub installments: u32,
+    pub installment_amount: i128,
+    pub total_repayment: i128,
+    pub total_interest: i128,
+    pub apr_bps: u32, // annualized cost of credit
+}
+
+// One installment as `make_payment` will apply it
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledPayment {
+    pub installment: u32, // 1-based
+    pub due_timestamp: u64,
+    pub payment: i128,
+    pub principal: i128,
+    pub interest: i128,
+    pub remaining_balance: i128,
+}
+
+// Legacy tiers (90+: 15%, 80+: 20%, 70+: 30%, else 40%) with no premiums
+pub fn default_table(env: &Env) -> PricingTable {
+    let mut score_bands = Vec::new(env);
+    for (min_score, rate_bps) in math::DEFAULT_SCORE_BANDS {
+        score_bands.push_back(ScoreBand { min_score, rate_bps });
+    }
+    PricingTable {
+        score_bands,
+        duration_bands: Vec::new(env),
+        size_bands: Vec::new(env),
+        utilization_kink_bps: math::DEFAULT_UTILIZATION_KINK_BPS,
+        utilization_premium_bps: 0,
+        max_rate_bps: math::DEFAULT_MAX_RATE_BPS,
+    }
+}
+
+pub fn validate_table(table: &PricingTable) {
+    assert!(!table.score_bands.is_empty(), "Score bands required");
+    assert!(table.score_bands.get(0).unwrap().min_score == 0, "First score band must start at 0");
+    for i in 1..table.score_bands.len() {
+        assert!(
+            table.score_bands.get(i - 1).unwrap().min_score < table.score_bands.get(i).unwrap().min_score,
+            "Score bands must be ascending"
+        );
+    }
+    for i in 1..table.duration_bands.len() {
+        assert!(
+            table.duration_bands.get(i - 1).unwrap().min_months <
+                table.duration_bands.get(i).unwrap().min_months,
+            "Duration bands must be ascending"
+        );
+    }
+    for i in 1..table.size_bands.len() {
+        assert!(
+            table.size_bands.get(i - 1).unwrap().min_amount < table.size_bands.get(i).unwrap().min_amount,
+            "Size bands must be ascending"
+        );
+    }
+    assert!(table.utilization_kink_bps < 10000, "Kink must be below 100%");
+    assert!(table.max_rate_bps > 0, "Max rate must be positive");
+}
+
+// Annual rate for a loan: score band, plus duration, size and utilization premiums
+pub fn rate_for(
+    table: &PricingTable,
+    score: u32,
+    duration_months: u32,
+    amount: i128,
+    utilization_bps: u32
+) -> u32 {
+    let base_rate = math::band_value(
+        table.score_bands.iter().map(|band| (band.min_score, band.rate_bps)),
+        score
+    );
+    let duration_premium = math::band_value(
+        table.duration_bands.iter().map(|band| (band.min_months, band.premium_bps)),
+        duration_months
+    );
+    let size_premium = math::band_value(
+        table.size_bands.iter().map(|band| (band.min_amount, band.premium_bps)),
+        amount
+    );
+    let utilization_premium = math::utilization_premium(
+        table.utilization_kink_bps,
+        table.utilization_premium_bps,
+        utilization_bps
+    );
+
+    math::capped_rate(
+        base_rate,
+        &[duration_premium, size_premium, utilization_premium],
+        table.max_rate_bps
+    )
+}
+
+pub fn quote(
+    env: &Env,
+    principal: i128,
+    origination_fee: i128,
+    annual_rate_bps: u32,
+    months: u32,
+    frequency: PaymentFrequency
+) -> LoanQuote {
+    let schedule = schedule(env, principal, annual_rate_bps, months, frequency, 0);
+    let total_repayment = total_of_payments(&schedule);
+
+    LoanQuote {
+        interest_rate: annual_rate_bps,
+        origination_fee,
+        frequency,
+        installments: frequency.installments(months),
+        installment_amount: installment_amount(principal, annual_rate_bps, months, frequency),
+        total_repayment,
+        total_interest: total_repayment - principal,
+        // The fee is withheld up front, so the borrower receives less than the principal
+        apr_bps: apr_bps(principal - origination_fee, &schedule, frequency),
+    }
+}
+
+// Replays the installments `make_payment` would apply over a term of `months`, with their due
+// dates
+pub fn schedule(
+    env: &Env,
+    principal: i128,
+    annual_rate_bps: u32,
+    months: u32,
+    frequency: PaymentFrequency,
+    start_timestamp: u64
+) -> Vec<ScheduledPayment> {
+    let mut payments = Vec::new(env);
+    let entries = math::schedule(
+        principal,
+        annual_rate_bps,
+        frequency.installments(months),
+        frequency.periods_per_year()
+    );
+    for entry in entries {
+        let elapsed = (entry.installment as u64) * frequency.period_seconds();
+        payments.push_back(ScheduledPayment {
+            installment: entry.installment,
+            due_timestamp: start_timestamp + elapsed,
+            payment: entry.payment,
+            principal: entry.principal,
+            interest: entry.interest,
+            remaining_balance: entry.remaining_balance,
+        });
+    }
+    payments
+}
+
+pub fn total_of_payments(schedule: &Vec<ScheduledPayment>) -> i128 {
+    let mut total: i128 = 0;
+    for entry in schedule.iter() {
+        total += entry.payment;
+    }
+    total
+}
+
+// Annual percentage rate (periodic IRR x cycles per year) that discounts the scheduled
+// payments back to `amount_financed`
+pub fn apr_bps(
+    amount_financed: i128,
+    schedule: &Vec<ScheduledPayment>,
+    frequency: PaymentFrequency
+) -> u32 {
+    assert!(schedule.len() as usize <= MAX_SCHEDULE_LEN, "Schedule too long");
+
+    let mut payments = [0i128; MAX_SCHEDULE_LEN];
+    for (slot, entry) in payments.iter_mut().zip(schedule.iter()) {
+        *slot = entry.payment;
+    }
+    math::apr_bps(amount_financed, &payments[..schedule.len() as usize], frequency.periods_per_year())
+}
@@ -0,0 +1,118 @@
+use soroban_sdk::contracttype;
+
+use crate::pricing;
+use crate::Loan;
+
+// What to do with money left over once the current installment is covered
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExcessPayment {
+    PayAhead = 0, // cover the following installments, keeping the schedule
+    ReducePrincipal = 1, // prepay principal; the installment stays and the loan ends sooner
+}
+
+// Where a payment went, in waterfall order
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PaymentAllocation {
+    pub fees: i128,
+    pub interest: i128,
+    pub principal_due: i128, // principal of installments falling due
+    pub principal_prepaid: i128, // principal paid beyond the schedule
+    pub cycles_satisfied: u32, // installments completed by this payment
+}
+
+impl PaymentAllocation {
+    pub fn principal(&self) -> i128 {
+        self.principal_due + self.principal_prepaid
+    }
+
+    // Fold in another payment's allocation, e.g. one cycle of a multi-cycle remittance
+    pub fn add(&mut self, other: &PaymentAllocation) {
+        self.fees += other.fees;
+        self.interest += other.interest;
+        self.principal_due += other.principal_due;
+        self.principal_prepaid += other.principal_prepaid;
+        self.cycles_satisfied += other.cycles_satisfied;
+    }
+}
+
+// Everything the borrower owes right now
+pub fn total_owed(loan: &Loan) -> i128 {
+    loan.fees_due + loan.accrued_interest + loan.outstanding_balance
+}
+
+// Fees plus what is left of the current installment, capped at what is owed. The cap counts
+// what this cycle has already paid, which no longer shows in the balance.
+pub fn installment_remaining(loan: &Loan) -> i128 {
+    let owed = loan.accrued_interest + loan.outstanding_balance;
+    let target = if loan.installment_amount > 0 {
+        core::cmp::min(loan.installment_amount, owed + loan.installment_paid)
+    } else {
+        owed
+    };
+    let remaining = if target > loan.installment_paid { target - loan.installment_paid } else { 0 };
+    loan.fees_due + remaining
+}
+
+// Apply `amount` to fees, then accrued interest, then the installment's principal. Each time
+// the installment is covered the cycle moves on; the excess is handled per `excess`.
+pub fn allocate(loan: &mut Loan, amount: i128, excess: ExcessPayment) -> PaymentAllocation {
+    let mut allocation = PaymentAllocation::default();
+    let mut remaining = amount;
+
+    loop {
+        let fees = core::cmp::min(remaining, loan.fees_due);
+        loan.fees_due -= fees;
+        allocation.fees += fees;
+        remaining -= fees;
+
+        let interest = core::cmp::min(remaining, loan.accrued_interest);
+        loan.accrued_interest -= interest;
+        loan.installment_paid += interest;
+        allocation.interest += interest;
+        remaining -= interest;
+
+        let still_due = installment_remaining(loan);
+        let principal = core::cmp::min(remaining, still_due);
+        loan.outstanding_balance -= principal;
+        loan.installment_paid += principal;
+        allocation.principal_due += principal;
+        remaining -= principal;
+
+        if installment_remaining(loan) > 0 {
+            // Partial payment: the cycle stays open until the rest arrives
+            break;
+        }
+
+        if excess == ExcessPayment::ReducePrincipal {
+            let prepaid = core::cmp::min(remaining, loan.outstanding_balance);
+            loan.outstanding_balance -= prepaid;
+            allocation.principal_prepaid += prepaid;
+            remaining -= prepaid;
+        }
+
+        advance_cycle(loan);
+        allocation.cycles_satisfied += 1;
+
+        if remaining <= 0 || total_owed(loan) <= 0 {
+            break;
+        }
+    }
+
+    allocation
+}
+
+// Close the current cycle: the next installment falls due a cycle later and accrues interest
+// on the remaining balance
+pub fn advance_cycle(loan: &mut Loan) {
+    loan.payments_made += 1;
+    loan.payments_missed = 0;
+    loan.installment_paid = 0;
+    loan.next_payment_due += loan.frequency.period_seconds();
+    loan.accrued_interest += pricing::interest_portion(
+        loan.outstanding_balance,
+        loan.interest_rate,
+        loan.frequency
+    );
+}
@@ -0,0 +1,610 @@
+use lending_pool::{ LendingPool, LendingPoolClient, Tranche };
+use remittance_nft::{ IdentityKey, PaymentRecord, RemittanceNFT, RemittanceNFTClient };
+use soroban_sdk::{
+    testutils::{ Address as _, Ledger as _ },
+    token::{ StellarAssetClient, TokenClient },
+    Address,
+    BytesN,
+    Env,
+    String,
+    Vec,
+};
+
+use crate::{
+    pricing,
+    CreditPolicy,
+    DeferralPolicy,
+    DeferredInterest,
+    DurationBand,
+    ExcessPayment,
+    LoanManager,
+    LoanManagerClient,
+    LoanStatus,
+    PaymentFrequency,
+    PricingTable,
+    RestructuringKind,
+    ScoreBand,
+    SizeBand,
+};
+
+const MONTHLY: PaymentFrequency = PaymentFrequency::Monthly;
+
+struct Setup<'a> {
+    env: Env,
+    client: LoanManagerClient<'a>,
+    nft: RemittanceNFTClient<'a>,
+    pool: LendingPoolClient<'a>,
+    usdc: StellarAssetClient<'a>,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let usdc_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
+
+    // The NFT and pool take the loan manager's address at construction, so pick it up front
+    let loan_manager = Address::generate(&env);
+    let nft = RemittanceNFTClient::new(&env, &env.register(RemittanceNFT, (&admin, &oracle, &loan_manager)));
+    let pool = LendingPoolClient::new(
+        &env,
+        &env.register(LendingPool, (&admin, &loan_manager, &usdc_address, 500u32))
+    );
+    let client = LoanManagerClient::new(
+        &env,
+        &env.register_at(
+            &loan_manager,
+            LoanManager,
+            (&admin, &nft.address, &pool.address, &oracle, &usdc_address)
+        )
+    );
+
+    Setup { usdc: StellarAssetClient::new(&env, &usdc_address), env, client, nft, pool }
+}
+
+// A second asset with its own funded pool, not yet registered with the loan manager
+fn second_pool<'a>(s: &Setup<'a>) -> (StellarAssetClient<'a>, LendingPoolClient<'a>) {
+    let admin = Address::generate(&s.env);
+    let lender = Address::generate(&s.env);
+    let asset = StellarAssetClient::new(
+        &s.env,
+        &s.env.register_stellar_asset_contract_v2(admin.clone()).address()
+    );
+    let pool = LendingPoolClient::new(
+        &s.env,
+        &s.env.register(LendingPool, (&admin, &s.client.address, &asset.address, 500u32))
+    );
+
+    asset.mint(&lender, &100_000i128);
+    pool.deposit(&lender, &100_000i128, &Tranche::Junior, &0u32);
+    (asset, pool)
+}
+
+// Mint an NFT whose history is `months` paid months followed by `missed` misses, remitting
+// 30_000 a month so the default credit policy leaves room for the loans under test
+fn mint_nft(s: &Setup, owner: &Address, months: u32, missed: u32) -> u64 {
+    let mut history: Vec<PaymentRecord> = Vec::new(&s.env);
+    for month_index in 1..=months + missed {
+        let paid = month_index <= months;
+        history.push_back(PaymentRecord {
+            month_index,
+            paid,
+            amount: if paid { 30_000 } else { 0 },
+        });
+    }
+    let identity = IdentityKey {
+        provider: String::from_str(&s.env, "wise"),
+        account_hash: BytesN::from_array(&s.env, &[months as u8; 32]),
+    };
+    s.nft.mint(owner, &identity, &30_000i128, &(months + missed), &((months as i128) * 30_000), &history)
+}
+
+fn table(s: &Setup) -> PricingTable {
+    PricingTable {
+        score_bands: Vec::from_array(
+            &s.env,
+            [ScoreBand { min_score: 0, rate_bps: 3000 }, ScoreBand { min_score: 90, rate_bps: 1000 }]
+        ),
+        duration_bands: Vec::from_array(&s.env, [DurationBand { min_months: 12, premium_bps: 200 }]),
+        size_bands: Vec::from_array(&s.env, [SizeBand { min_amount: 50_000, premium_bps: 300 }]),
+        utilization_kink_bps: 8000,
+        utilization_premium_bps: 1000,
+        max_rate_bps: 2500,
+    }
+}
+
+#[test]
+fn quote_uses_the_default_tiers() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+
+    let quote = s.client.quote_loan(&nft_id, &10_000i128, &6u32, &s.usdc.address, &MONTHLY);
+
+    assert_eq!(quote.interest_rate, 1500);
+    assert_eq!(quote, pricing::quote(&s.env, 10_000, 0, 1500, 6, MONTHLY));
+    assert_eq!(quote.total_interest, quote.total_repayment - 10_000);
+}
+
+#[test]
+fn disclosure_matches_its_schedule() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+
+    let disclosure = s.client.disclosure(&nft_id, &12_000i128, &12u32, &s.usdc.address, &MONTHLY);
+
+    assert_eq!(disclosure.schedule.len(), 12);
+    assert_eq!(disclosure.total_of_payments, disclosure.amount_financed + disclosure.finance_charge);
+    let quote = s.client.quote_loan(&nft_id, &12_000i128, &12u32, &s.usdc.address, &MONTHLY);
+    assert_eq!(disclosure.apr_bps, quote.apr_bps);
+
+    // The schedule pays the loan off exactly
+    let mut principal = 0i128;
+    for entry in disclosure.schedule.iter() {
+        principal += entry.principal;
+    }
+    assert_eq!(principal, 12_000);
+    assert_eq!(disclosure.schedule.last().unwrap().remaining_balance, 0);
+}
+
+#[test]
+fn origination_fee_is_withheld_for_the_treasury() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let lender = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    s.usdc.mint(&lender, &100_000i128);
+    s.pool.deposit(&lender, &100_000i128, &Tranche::Junior, &0u32);
+    s.client.set_origination_fee(&200u32);
+
+    let disclosure = s.client.disclosure(&nft_id, &10_000i128, &12u32, &s.usdc.address, &MONTHLY);
+    assert_eq!(disclosure.origination_fee, 200);
+    assert_eq!(disclosure.amount_financed, 9_800);
+    assert_eq!(disclosure.finance_charge, disclosure.total_of_payments - 9_800);
+
+    let loan_id = s.client.request_loan(
+        &borrower,
+        &nft_id,
+        &10_000i128,
+        &12u32,
+        &s.usdc.address,
+        &MONTHLY
+    );
+    s.client.approve_loan(&loan_id);
+
+    // The borrower receives the net amount but owes the full principal
+    let usdc = TokenClient::new(&s.env, &s.usdc.address);
+    assert_eq!(usdc.balance(&borrower), 9_800);
+    assert_eq!(s.client.get_loan(&loan_id).outstanding_balance, 10_000);
+    assert_eq!(s.pool.get_treasury_balance(), 200);
+}
+
+#[test]
+fn payments_are_split_on_what_the_borrower_sends() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let lender = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    s.usdc.mint(&lender, &100_000i128);
+    s.pool.deposit(&lender, &100_000i128, &Tranche::Junior, &0u32);
+
+    let loan_id = s.client.request_loan(
+        &borrower,
+        &nft_id,
+        &10_000i128,
+        &6u32,
+        &s.usdc.address,
+        &MONTHLY
+    );
+    s.client.approve_loan(&loan_id);
+    s.usdc.mint(&borrower, &1_000i128);
+    let interest_due = pricing::interest_portion(
+        10_000,
+        s.client.get_loan(&loan_id).interest_rate,
+        MONTHLY
+    );
+
+    // A payment short of the interest due is all interest and leaves the principal alone
+    let partial = s.client.make_payment(&loan_id, &(interest_due - 25), &ExcessPayment::PayAhead);
+    assert_eq!(partial.interest, interest_due - 25);
+    assert_eq!(partial.cycles_satisfied, 0);
+    let loan = s.client.get_loan(&loan_id);
+    assert_eq!(loan.outstanding_balance, 10_000);
+    assert_eq!(loan.total_repaid, interest_due - 25);
+
+    // Nothing beyond the balance plus the interest still accrued is taken
+    let owed = s.client.get_payoff_amount(&loan_id);
+    assert_eq!(owed, 10_000 + 25);
+    assert!(s.client.try_make_payment(&loan_id, &(owed + 1), &ExcessPayment::ReducePrincipal).is_err());
+
+    s.client.make_payment(&loan_id, &owed, &ExcessPayment::ReducePrincipal);
+    let loan = s.client.get_loan(&loan_id);
+    assert_eq!(loan.outstanding_balance, 0);
+    assert_eq!(loan.status, LoanStatus::Repaid);
+}
+
+#[test]
+fn weekly_loans_fall_due_every_week() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+
+    let weekly = PaymentFrequency::Weekly;
+    let quote = s.client.quote_loan(&nft_id, &10_000i128, &6u32, &s.usdc.address, &weekly);
+    assert_eq!(quote.installments, 26);
+    let loan_id = s.client.request_loan(
+        &borrower,
+        &nft_id,
+        &10_000i128,
+        &6u32,
+        &s.usdc.address,
+        &weekly
+    );
+    let loan = s.client.get_loan(&loan_id);
+    assert_eq!(loan.installment_amount, quote.installment_amount);
+    let monthly = s.client.quote_loan(&nft_id, &10_000i128, &6u32, &s.usdc.address, &MONTHLY);
+    assert!(loan.installment_amount < monthly.installment_amount);
+
+    // A remittance two weeks after the first due date covers three installments
+    let week = weekly.period_seconds();
+    assert_eq!(s.client.get_cycles_due(&loan_id, &(loan.next_payment_due + 2 * week)), 3);
+}
+
+#[test]
+fn automatic_repayments_need_the_oracle_and_a_remittance_in_the_term() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let lender = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    s.usdc.mint(&lender, &100_000i128);
+    s.pool.deposit(&lender, &100_000i128, &Tranche::Junior, &0u32);
+
+    s.env.ledger().set_timestamp(1_000);
+    let loan_id = s.client.request_loan(
+        &borrower,
+        &nft_id,
+        &10_000i128,
+        &6u32,
+        &s.usdc.address,
+        &MONTHLY
+    );
+    s.client.approve_loan(&loan_id);
+    let loan = s.client.get_loan(&loan_id);
+    s.usdc.mint(&borrower, &1_000i128);
+    s.env.ledger().set_timestamp(loan.next_payment_due);
+
+    let amount = loan.installment_amount + 5;
+    let before_start = loan.start_timestamp - 1;
+    assert!(s.client.try_process_automatic_repayment(&loan_id, &amount, &before_start).is_err());
+    let future = loan.next_payment_due + 1;
+    assert!(s.client.try_process_automatic_repayment(&loan_id, &amount, &future).is_err());
+
+    let oracle = s.client.get_config().oracle_contract;
+    assert_eq!(s.client.process_automatic_repayment(&loan_id, &amount, &loan.next_payment_due), 5);
+    assert!(s.env.auths().iter().any(|(address, _)| *address == oracle));
+    assert_eq!(s.client.get_loan(&loan_id).payments_made, 1);
+}
+
+#[test]
+fn low_scores_fall_into_a_lower_tier() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 6, 3);
+
+    let score = s.nft.get_nft_data(&nft_id).reliability_score;
+    assert!(score < 70);
+    assert_eq!(
+        s.client.quote_loan(&nft_id, &10_000i128, &6u32, &s.usdc.address, &MONTHLY).interest_rate,
+        4000
+    );
+}
+
+#[test]
+fn pricing_table_adds_duration_and_size_premiums() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    s.client.set_pricing_table(&table(&s));
+
+    assert_eq!(s.client.get_pricing_table(), table(&s));
+    assert_eq!(
+        s.client.quote_loan(&nft_id, &10_000i128, &6u32, &s.usdc.address, &MONTHLY).interest_rate,
+        1000
+    );
+    assert_eq!(
+        s.client.quote_loan(&nft_id, &10_000i128, &12u32, &s.usdc.address, &MONTHLY).interest_rate,
+        1200
+    );
+    assert_eq!(
+        s.client.quote_loan(&nft_id, &50_000i128, &12u32, &s.usdc.address, &MONTHLY).interest_rate,
+        1500
+    );
+
+    // The loan is priced exactly as quoted
+    let loan_id = s.client.request_loan(
+        &borrower,
+        &nft_id,
+        &50_000i128,
+        &12u32,
+        &s.usdc.address,
+        &MONTHLY
+    );
+    let loan = s.client.get_loan(&loan_id);
+    assert_eq!(loan.interest_rate, 1500);
+    assert_eq!(loan.installment_amount, pricing::installment_amount(50_000, 1500, 12, MONTHLY));
+}
+
+#[test]
+fn utilization_above_the_kink_adds_a_capped_premium() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let lender = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    let mut capped = table(&s);
+    capped.max_rate_bps = 1800;
+    s.client.set_pricing_table(&capped);
+
+    s.usdc.mint(&lender, &100_000i128);
+    s.pool.deposit(&lender, &100_000i128, &Tranche::Junior, &0u32);
+    s.pool.borrow(&90_000i128, &0i128, &borrower, &1u64);
+    assert_eq!(s.pool.get_utilization_rate(), 9000);
+
+    // Half way from the kink to full utilization earns half the premium
+    assert_eq!(
+        s.client.quote_loan(&nft_id, &10_000i128, &6u32, &s.usdc.address, &MONTHLY).interest_rate,
+        1500
+    );
+    // Every premium together is capped at the table's max rate
+    assert_eq!(
+        s.client.quote_loan(&nft_id, &50_000i128, &12u32, &s.usdc.address, &MONTHLY).interest_rate,
+        1800
+    );
+}
+
+#[test]
+fn set_pricing_table_rejects_invalid_tables() {
+    let s = setup();
+
+    let mut unordered = table(&s);
+    unordered.score_bands = Vec::from_array(
+        &s.env,
+        [ScoreBand { min_score: 0, rate_bps: 3000 }, ScoreBand { min_score: 0, rate_bps: 1000 }]
+    );
+    assert!(s.client.try_set_pricing_table(&unordered).is_err());
+
+    let mut no_floor = table(&s);
+    no_floor.score_bands = Vec::from_array(&s.env, [ScoreBand { min_score: 50, rate_bps: 3000 }]);
+    assert!(s.client.try_set_pricing_table(&no_floor).is_err());
+
+    assert_eq!(s.client.get_pricing_table(), pricing::default_table(&s.env));
+}
+
+#[test]
+fn refinance_needs_a_cheaper_rate() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let lender = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    s.usdc.mint(&lender, &100_000i128);
+    s.pool.deposit(&lender, &100_000i128, &Tranche::Junior, &0u32);
+
+    let loan_id = s.client.request_loan(
+        &borrower,
+        &nft_id,
+        &10_000i128,
+        &6u32,
+        &s.usdc.address,
+        &MONTHLY
+    );
+    s.client.approve_loan(&loan_id);
+    assert!(s.client.try_refinance(&loan_id, &6u32).is_err());
+
+    s.client.set_pricing_table(&table(&s));
+    let before = s.client.get_loan(&loan_id);
+    assert_eq!(s.client.refinance(&loan_id, &6u32), 1000);
+
+    let loan = s.client.get_loan(&loan_id);
+    assert_eq!(loan.interest_rate, 1000);
+    assert!(loan.installment_amount < before.installment_amount);
+    let entry = loan.restructurings.get(0).unwrap();
+    assert_eq!(entry.kind, RestructuringKind::Refinanced);
+    assert_eq!(entry.previous_rate, before.interest_rate);
+    assert_eq!(entry.outstanding_balance, 10_000);
+}
+
+#[test]
+fn overdue_installments_cannot_be_deferred() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let lender = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    s.usdc.mint(&lender, &100_000i128);
+    s.pool.deposit(&lender, &100_000i128, &Tranche::Junior, &0u32);
+
+    // Every request waits for the admin
+    s.client.set_deferral_policy(
+        &(DeferralPolicy {
+            max_per_loan: 2,
+            auto_approve_min_score: 101,
+            interest: DeferredInterest::Waive,
+        })
+    );
+    let loan_id = s.client.request_loan(
+        &borrower,
+        &nft_id,
+        &10_000i128,
+        &6u32,
+        &s.usdc.address,
+        &MONTHLY
+    );
+    s.client.approve_loan(&loan_id);
+    assert!(!s.client.request_deferral(&loan_id));
+
+    // The installment falls due before the admin gets to the request
+    let due = s.client.get_loan(&loan_id).next_payment_due;
+    s.env.ledger().set_timestamp(due + 1);
+    assert!(s.client.try_approve_deferral(&loan_id).is_err());
+    s.client.reject_deferral(&loan_id);
+    assert!(s.client.try_request_deferral(&loan_id).is_err());
+
+    let loan = s.client.get_loan(&loan_id);
+    assert_eq!(loan.deferrals_used, 0);
+    assert_eq!(loan.next_payment_due, due);
+}
+
+#[test]
+fn loans_are_funded_by_the_pool_for_their_asset() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    let (eurc, eurc_pool) = second_pool(&s);
+
+    s.client.add_pool(&eurc.address, &eurc_pool.address);
+    assert_eq!(s.client.get_pool(&eurc.address), eurc_pool.address);
+    assert_eq!(
+        s.client.get_supported_assets(),
+        Vec::from_array(&s.env, [s.usdc.address.clone(), eurc.address.clone()])
+    );
+
+    let loan_id = s.client.request_loan(&borrower, &nft_id, &10_000i128, &6u32, &eurc.address, &MONTHLY);
+    s.client.approve_loan(&loan_id);
+
+    assert_eq!(s.client.get_loan(&loan_id).asset, eurc.address);
+    assert_eq!(TokenClient::new(&s.env, &eurc.address).balance(&borrower), 10_000);
+    assert_eq!(eurc_pool.get_available_liquidity(), 90_000);
+    assert_eq!(s.pool.get_available_liquidity(), 0);
+}
+
+#[test]
+fn add_pool_rejects_a_pool_for_another_asset() {
+    let s = setup();
+    let (eurc, _) = second_pool(&s);
+
+    assert!(s.client.try_add_pool(&eurc.address, &s.pool.address).is_err());
+    assert!(s.client.try_get_pool(&eurc.address).is_err());
+}
+
+#[test]
+fn config_follows_the_address_setters() {
+    let s = setup();
+    let (eurc, eurc_pool) = second_pool(&s);
+    let nft = Address::generate(&s.env);
+    let oracle = Address::generate(&s.env);
+
+    s.client.set_nft_contract(&nft);
+    s.client.set_oracle_contract(&oracle);
+    s.client.add_pool(&eurc.address, &eurc_pool.address);
+
+    let config = s.client.get_config();
+    assert_eq!(config.nft_contract, nft);
+    assert_eq!(config.oracle_contract, oracle);
+    assert_eq!(config.default_asset, s.usdc.address);
+    assert_eq!(config.supported_assets, Vec::from_array(&s.env, [s.usdc.address.clone(), eurc.address]));
+}
+
+#[test]
+fn removed_pools_take_no_new_loans_but_keep_collecting() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    let (eurc, eurc_pool) = second_pool(&s);
+    s.client.add_pool(&eurc.address, &eurc_pool.address);
+
+    let loan_id = s.client.request_loan(&borrower, &nft_id, &10_000i128, &6u32, &eurc.address, &MONTHLY);
+    s.client.approve_loan(&loan_id);
+    s.client.remove_pool(&eurc.address);
+
+    assert!(!s.client.get_supported_assets().contains(&eurc.address));
+    let request = s.client.try_request_loan(
+        &borrower,
+        &nft_id,
+        &10_000i128,
+        &6u32,
+        &eurc.address,
+        &MONTHLY
+    );
+    assert!(request.is_err());
+
+    // The outstanding loan is still repaid into its pool
+    let payment = s.client.get_loan(&loan_id).installment_amount;
+    s.client.make_payment(&loan_id, &payment, &ExcessPayment::PayAhead);
+    assert!(eurc_pool.get_available_liquidity() > 90_000);
+}
+
+#[test]
+fn loans_must_fit_the_borrowers_credit_limit() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    let score = s.nft.get_nft_data(&nft_id).reliability_score as i128;
+
+    // Three months of remittances, scaled by score
+    let exposure = s.client.get_borrower_exposure(&borrower, &nft_id);
+    assert_eq!(exposure.credit_limit, (90_000 * score) / 100);
+    assert_eq!(exposure.available_credit, exposure.credit_limit);
+
+    let too_much = exposure.credit_limit + 1;
+    assert!(
+        s.client
+            .try_request_loan(&borrower, &nft_id, &too_much, &24u32, &s.usdc.address, &MONTHLY)
+            .is_err()
+    );
+
+    s.client.request_loan(&borrower, &nft_id, &40_000i128, &24u32, &s.usdc.address, &MONTHLY);
+    let exposure = s.client.get_borrower_exposure(&borrower, &nft_id);
+    assert_eq!(exposure.open_loans, 1);
+    assert_eq!(exposure.outstanding, 40_000);
+    assert_eq!(exposure.available_credit, exposure.credit_limit - 40_000);
+}
+
+#[test]
+fn credit_policy_caps_open_loans_and_debt_to_income() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 12, 0);
+    let policy = CreditPolicy {
+        income_multiple_bps: 30000,
+        min_score: 50,
+        max_active_loans: 1,
+        max_debt_to_income_bps: 1000,
+    };
+    s.client.set_credit_policy(&policy);
+    assert_eq!(s.client.get_credit_policy(), policy);
+
+    // Installments on 50_000 over a year are well over 10% of a 30_000 monthly remittance
+    assert!(
+        s.client
+            .try_request_loan(&borrower, &nft_id, &50_000i128, &12u32, &s.usdc.address, &MONTHLY)
+            .is_err()
+    );
+
+    s.client.request_loan(&borrower, &nft_id, &10_000i128, &6u32, &s.usdc.address, &MONTHLY);
+    assert!(
+        s.client
+            .try_request_loan(&borrower, &nft_id, &1_000i128, &6u32, &s.usdc.address, &MONTHLY)
+            .is_err()
+    );
+}
+
+#[test]
+fn borrowers_below_the_min_score_get_no_credit() {
+    let s = setup();
+    let borrower = Address::generate(&s.env);
+    let nft_id = mint_nft(&s, &borrower, 6, 3);
+    let mut policy = s.client.get_credit_policy();
+    policy.min_score = 90;
+    s.client.set_credit_policy(&policy);
+
+    assert_eq!(s.client.get_borrower_exposure(&borrower, &nft_id).credit_limit, 0);
+    assert!(
+        s.client
+            .try_request_loan(&borrower, &nft_id, &1_000i128, &6u32, &s.usdc.address, &MONTHLY)
+            .is_err()
+    );
+
+    policy.max_active_loans = 0;
+    assert!(s.client.try_set_credit_policy(&policy).is_err());
+}
@@ -1,5 +1,15 @@
 #![no_std]
-use soroban_sdk::{ contract, contractimpl, contracttype, Address, String, Env, Vec };
+use soroban_sdk::{
+    contract,
+    contractevent,
+    contractimpl,
+    contracttype,
+    xdr::ToXdr,
+    Address,
+    String,
+    Env,
+    Vec,
+};
 
 #[contracttype]
 #[derive(Clone)]
@@ -24,6 +34,7 @@ pub enum VerificationStatus {
 pub struct PaymentRecord {
     pub month_index: u32,
     pub paid: bool,
+    pub amount: i128,
 }
 
 #[contracttype]
@@ -34,40 +45,145 @@ pub enum DataKey {
     RemittanceNFTContract,
     LoanManagerContract,
     MonitoredLoans(u64), // loan_id -> bool (is being monitored)
+    AdminAddress,
 }
 
+#[contracttype]
+#[derive(Clone)]
+pub struct OracleConfig {
+    pub admin: Address,
+    pub nft_contract: Address,
+    pub loan_manager: Address,
+    pub operators: Vec<Address>,
+}
+
+#[contractevent]
+pub struct VerificationRequested {
+    pub user: Address,
+}
+
+#[contractevent]
+pub struct VerificationComplete {
+    #[topic]
+    pub user: Address,
+    pub reliability_score: u32,
+}
+
+#[contractevent]
+pub struct MonitoringStarted {
+    pub loan_id: u64,
+}
+
+#[contractevent]
+pub struct RemittanceReported {
+    #[topic]
+    pub loan_id: u64,
+    #[topic]
+    pub nft_id: u64,
+    pub amount: i128,
+    pub cycles: u32,
+}
+
+#[contractevent]
+pub struct PaymentMissedReported {
+    #[topic]
+    pub loan_id: u64,
+    pub nft_id: u64,
+}
+
+#[contractevent]
+pub struct NftContractSet {
+    pub nft_contract: Address,
+}
+
+#[contractevent]
+pub struct LoanManagerSet {
+    pub loan_manager: Address,
+}
+
+#[contractevent]
+pub struct OperatorsSet {
+    pub count: u32,
+}
+
+// Native builds link the contract crates instead of importing their wasm
+#[cfg(target_family = "wasm")]
 mod remittance {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/release/remittance_nft.wasm"
     );
 }
 
+#[cfg(not(target_family = "wasm"))]
+mod remittance {
+    pub use ::remittance_nft::{ IdentityKey, PaymentRecord, RemittanceNFTClient as Client };
+}
+
+#[cfg(target_family = "wasm")]
 mod loan_manager {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/release/loan_manager.wasm"
     );
 }
 
+#[cfg(not(target_family = "wasm"))]
+mod loan_manager {
+    pub use ::loan_manager::LoanManagerClient as Client;
+}
+
 #[contract]
 pub struct OracleVerifier;
 
 #[contractimpl]
 impl OracleVerifier {
-    pub fn __initialize(
+    // Runs once at deployment
+    pub fn __constructor(
         env: Env,
+        admin: Address,
         nft_contract: Address,
         loan_manager: Address,
         operators: Vec<Address>
     ) {
+        env.storage().instance().set(&DataKey::AdminAddress, &admin);
         env.storage().instance().set(&DataKey::RemittanceNFTContract, &nft_contract);
         env.storage().instance().set(&DataKey::LoanManagerContract, &loan_manager);
+        Self::store_operators(&env, &operators);
+    }
 
-        // Set oracle operators
-        let count = operators.len();
+    // Repoint the oracle at a redeployed RemittanceNFT (admin only)
+    pub fn set_nft_contract(env: Env, nft_contract: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::RemittanceNFTContract, &nft_contract);
+        (NftContractSet { nft_contract }).publish(&env);
+    }
+
+    // Repoint the oracle at a redeployed LoanManager (admin only)
+    pub fn set_loan_manager(env: Env, loan_manager: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::LoanManagerContract, &loan_manager);
+        (LoanManagerSet { loan_manager }).publish(&env);
+    }
+
+    // Replace the set of authorized oracle operators (admin only)
+    pub fn set_operators(env: Env, operators: Vec<Address>) {
+        Self::require_admin(&env);
+        Self::store_operators(&env, &operators);
+        (OperatorsSet { count: operators.len() }).publish(&env);
+    }
+
+    pub fn get_config(env: Env) -> OracleConfig {
+        let count: u32 = env.storage().instance().get(&DataKey::OracleOperatorCount).unwrap_or(0);
+        let mut operators = Vec::new(&env);
         for i in 0..count {
-            env.storage().instance().set(&DataKey::OracleOperators(i), &operators.get(i).unwrap());
+            operators.push_back(env.storage().instance().get(&DataKey::OracleOperators(i)).unwrap());
+        }
+
+        OracleConfig {
+            admin: env.storage().instance().get(&DataKey::AdminAddress).expect("Admin not configured"),
+            nft_contract: env.storage().instance().get(&DataKey::RemittanceNFTContract).unwrap(),
+            loan_manager: env.storage().instance().get(&DataKey::LoanManagerContract).unwrap(),
+            operators,
         }
-        env.storage().instance().set(&DataKey::OracleOperatorCount, &count);
     }
 
     pub fn request_verification(env: Env, user: Address, provider: String, account_id: String) {
@@ -83,7 +199,7 @@ impl OracleVerifier {
 
         env.storage().instance().set(&DataKey::VerificationRequest(user.clone()), &request);
 
-        env.events().publish(("verification_requested",), user);
+        (VerificationRequested { user }).publish(&env);
     }
 
     // Oracle operator submits verification result
@@ -108,9 +224,6 @@ impl OracleVerifier {
 
         assert!(request.status == VerificationStatus::Pending, "Already processed");
 
-        // Calculate reliability score
-        let reliability_score = Self::calculate_reliability_score(&payment_history);
-
         // Call RemittanceNFT contract to mint
         let nft_contract: Address = env
             .storage()
@@ -118,22 +231,27 @@ impl OracleVerifier {
             .get(&DataKey::RemittanceNFTContract)
             .unwrap();
 
-        // In real implementation:
+        // One token per remittance account: re-verification refreshes the existing token
+        let identity = Self::identity_key(&env, &request);
+
         let nft_client = remittance::Client::new(&env, &nft_contract);
         let token_id = nft_client.mint(
             &user,
+            &identity,
             &monthly_amount,
-            &reliability_score,
             &history_months,
             &total_sent,
             &payment_history
         );
 
+        // The NFT contract owns the scoring model
+        let reliability_score = nft_client.get_nft_data(&token_id).reliability_score;
+
         // Update request status
         request.status = VerificationStatus::Verified;
         env.storage().instance().set(&DataKey::VerificationRequest(user.clone()), &request);
 
-        env.events().publish(("verification_complete", user), reliability_score);
+        (VerificationComplete { user, reliability_score }).publish(&env);
     }
 
     // Start monitoring loan for automatic repayments
@@ -147,17 +265,19 @@ impl OracleVerifier {
 
         env.storage().instance().set(&DataKey::MonitoredLoans(loan_id), &true);
 
-        env.events().publish(("monitoring_started",), loan_id);
+        (MonitoringStarted { loan_id }).publish(&env);
     }
 
-    // Oracle detects remittance and triggers automatic repayment
+    // Oracle detects remittance and triggers automatic repayment. `remitted_at` is when the
+    // provider recorded the transfer; it decides which installment cycles the remittance pays.
     pub fn report_remittance(
         env: Env,
         operator: Address,
         user: Address,
         nft_id: u64,
         amount: i128,
-        loan_id: u64
+        loan_id: u64,
+        remitted_at: u64
     ) {
         Self::verify_operator(&env, &operator);
         operator.require_auth();
@@ -179,7 +299,7 @@ impl OracleVerifier {
             .unwrap();
         let nft_client = remittance::Client::new(&env, &nft_contract);
 
-        nft_client.update_remittance_data(&nft_id, &amount, &0_i128);
+        nft_client.update_remittance_data(&nft_id, &amount);
 
         // Process automatic repayment through LoanManager
         let loan_manager: Address = env
@@ -188,11 +308,13 @@ impl OracleVerifier {
             .get(&DataKey::LoanManagerContract)
             .unwrap();
         let loan_manager_client = loan_manager::Client::new(&env, &loan_manager);
+        assert!(loan_manager_client.get_loan(&loan_id).borrower == user, "Loan not owned by user");
 
-        loan_manager_client.process_automatic_repayment(&loan_id, &amount);
-        // let remaining = loan_manager.process_automatic_repayment(loan_id, amount)
+        let cycles = loan_manager_client.get_cycles_due(&loan_id, &remitted_at);
+        loan_manager_client.process_automatic_repayment(&loan_id, &amount, &remitted_at);
+        // let remaining = loan_manager.process_automatic_repayment(loan_id, amount, remitted_at)
 
-        env.events().publish(("remittance_reported", loan_id, nft_id), amount);
+        (RemittanceReported { loan_id, nft_id, amount, cycles }).publish(&env);
     }
 
     // Oracle reports missed payment
@@ -200,6 +322,16 @@ impl OracleVerifier {
         Self::verify_operator(&env, &operator);
         operator.require_auth();
 
+        let loan_manager: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::LoanManagerContract)
+            .unwrap();
+        let loan_manager_client = loan_manager::Client::new(&env, &loan_manager);
+
+        // A cycle skipped under a granted deferral is not a miss, on the loan or the NFT
+        assert!(!loan_manager_client.is_payment_deferred(&loan_id), "Payment deferred");
+
         // Update NFT
         let nft_contract: Address = env
             .storage()
@@ -212,16 +344,9 @@ impl OracleVerifier {
         nft_client.mark_payment_missed(&nft_id);
 
         // Update loan
-        let loan_manager: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::LoanManagerContract)
-            .unwrap();
-        let loan_manager_client = loan_manager::Client::new(&env, &loan_manager);
-
         loan_manager_client.mark_payment_missed(&loan_id);
 
-        env.events().publish(("payment_missed_reported", loan_id), nft_id);
+        (PaymentMissedReported { loan_id, nft_id }).publish(&env);
     }
 
     // Get verification status
@@ -235,6 +360,55 @@ impl OracleVerifier {
         request.status
     }
 
+    // Token minted for a user's verified remittance account, if any
+    pub fn get_verified_token(env: Env, user: Address) -> Option<u64> {
+        let request: VerificationRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::VerificationRequest(user))?;
+        if request.status != VerificationStatus::Verified {
+            return None;
+        }
+
+        let nft_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::RemittanceNFTContract)
+            .unwrap();
+        remittance::Client::new(&env, &nft_contract).get_token_by_identity(&Self::identity_key(&env, &request))
+    }
+
+    // Internal: Identity the NFT contract keys a remittance account's token on
+    fn identity_key(env: &Env, request: &VerificationRequest) -> remittance::IdentityKey {
+        remittance::IdentityKey {
+            provider: request.provider.clone(),
+            account_hash: env.crypto().sha256(&request.account_id.clone().to_xdr(env)).into(),
+        }
+    }
+
+    // Internal: Require auth from the stored admin
+    fn require_admin(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminAddress)
+            .expect("Admin not configured");
+        admin.require_auth();
+    }
+
+    // Internal: Overwrite the indexed operator list, clearing any stale entries past the end
+    fn store_operators(env: &Env, operators: &Vec<Address>) {
+        let old_count: u32 = env.storage().instance().get(&DataKey::OracleOperatorCount).unwrap_or(0);
+        let count = operators.len();
+        for i in 0..count {
+            env.storage().instance().set(&DataKey::OracleOperators(i), &operators.get(i).unwrap());
+        }
+        for i in count..old_count {
+            env.storage().instance().remove(&DataKey::OracleOperators(i));
+        }
+        env.storage().instance().set(&DataKey::OracleOperatorCount, &count);
+    }
+
     // Internal: Verify operator is authorized
     fn verify_operator(env: &Env, operator: &Address) {
         let count: u32 = env.storage().instance().get(&DataKey::OracleOperatorCount).unwrap();
@@ -252,22 +426,4 @@ impl OracleVerifier {
 
         panic!("Unauthorized operator");
     }
-
-    // Internal: Calculate reliability score from payment history
-    fn calculate_reliability_score(payment_history: &Vec<remittance::PaymentRecord>) -> u32 {
-        let mut paid_count = 0u32;
-        let total_count = payment_history.len() as u32;
-
-        if total_count == 0 {
-            return 100;
-        }
-
-        for i in 0..payment_history.len() {
-            if payment_history.get(i).unwrap().paid {
-                paid_count += 1;
-            }
-        }
-
-        (paid_count * 100) / total_count
-    }
 }
@@ -1,6 +1,24 @@
 #![no_std]
 
-use soroban_sdk::{ contract, contractimpl, contracttype, Address, Env, Vec };
+use soroban_sdk::{
+    contract,
+    contractevent,
+    contractimpl,
+    contracttype,
+    Address,
+    BytesN,
+    Env,
+    String,
+    Vec,
+};
+
+mod metadata;
+mod scoring;
+
+pub use scoring::ScoreBreakdown;
+
+#[cfg(test)]
+mod test;
 
 #[contracttype]
 #[derive(Clone)]
@@ -21,8 +39,52 @@ pub struct RemittanceData {
 pub struct PaymentRecord {
     pub month_index: u32,
     pub paid: bool, // true = paid, false = missed
+    pub amount: i128, // Amount remitted that month (0 if missed)
 }
 
+// Verified remittance account that backs a token: provider plus sha256 of the account id
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub struct IdentityKey {
+    pub provider: String,
+    pub account_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScoreChangeReason {
+    Mint = 0,
+    Refresh = 1,
+    Payment = 2,
+    Missed = 3,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreSnapshot {
+    pub timestamp: u64,
+    pub score: u32,
+    pub reason: ScoreChangeReason,
+}
+
+// Emitted when a score moves across one of the configured pricing tier thresholds
+#[contractevent]
+pub struct TierChanged {
+    #[topic]
+    pub token_id: u64,
+    pub old_tier: u32,
+    pub new_tier: u32,
+    pub score: u32,
+}
+
+#[contractevent]
+pub struct TierThresholdsSet {
+    pub thresholds: Vec<u32>,
+}
+
+// Number of snapshots kept per token; the oldest is dropped first
+const MAX_SCORE_SNAPSHOTS: u32 = 36;
+
 #[contracttype]
 pub enum DataKey {
     TokenCounter,
@@ -30,6 +92,106 @@ pub enum DataKey {
     PaymentHistory(u64), // token_id -> Vec<PaymentRecord>
     OracleAddress,
     LoanManagerAddress,
+    AdminAddress,
+    Soulbound,
+    OwnerTokens(Address), // owner -> Vec<u64> of token_ids
+    Approval(u64), // token_id -> approved spender
+    IdentityToken(IdentityKey), // identity -> token_id
+    TokenIdentity(u64), // token_id -> IdentityKey
+    ScoreHistory(u64), // token_id -> Vec<ScoreSnapshot>
+    TierThresholds, // Vec<u32> of ascending score thresholds
+}
+
+#[contractevent]
+pub struct MintNft {
+    #[topic]
+    pub owner: Address,
+    pub token_id: u64,
+}
+
+#[contractevent]
+pub struct RefreshNft {
+    #[topic]
+    pub owner: Address,
+    pub token_id: u64,
+}
+
+#[contractevent]
+pub struct StakeNft {
+    #[topic]
+    pub token_id: u64,
+    pub loan_id: u64,
+}
+
+#[contractevent]
+pub struct UnstakeNft {
+    pub token_id: u64,
+}
+
+#[contractevent]
+pub struct UpdateNft {
+    #[topic]
+    pub token_id: u64,
+    pub reliability_score: u32,
+}
+
+#[contractevent]
+pub struct PaymentMissed {
+    #[topic]
+    pub token_id: u64,
+    pub reliability_score: u32,
+}
+
+#[contractevent]
+pub struct Approve {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub token_id: u64,
+    pub approved: Address,
+}
+
+#[contractevent]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub token_id: u64,
+}
+
+// A transfer made by the admin on the owner's behalf
+#[contractevent]
+pub struct RecoverNft {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub token_id: u64,
+}
+
+#[contractevent]
+pub struct SoulboundSet {
+    pub soulbound: bool,
+}
+
+#[contractevent]
+pub struct OracleSet {
+    pub oracle: Address,
+}
+
+#[contractevent]
+pub struct LoanManagerSet {
+    pub loan_manager: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct NftConfig {
+    pub admin: Address,
+    pub oracle: Address,
+    pub loan_manager: Address,
+    pub soulbound: bool,
 }
 
 #[contract]
@@ -37,35 +199,119 @@ pub struct RemittanceNFT;
 
 #[contractimpl]
 impl RemittanceNFT {
-    pub fn __initialize(env: Env, oracle: Address, loan_manager: Address) {
+    // Runs once at deployment
+    pub fn __constructor(env: Env, admin: Address, oracle: Address, loan_manager: Address) {
         env.storage().instance().set(&DataKey::OracleAddress, &oracle);
         env.storage().instance().set(&DataKey::LoanManagerAddress, &loan_manager);
+        env.storage().instance().set(&DataKey::AdminAddress, &admin);
         env.storage().instance().set(&DataKey::TokenCounter, &0u64);
+        env.storage().instance().set(&DataKey::Soulbound, &true);
     }
 
-    // Public initialize function that can be called after deployment
-    pub fn initialize(env: Env, admin: Address, oracle: Address, loan_manager: Address) {
-        admin.require_auth();
-
-        // Only allow initialization if not already initialized
-        let existing: Option<Address> = env.storage().instance().get(&DataKey::OracleAddress);
-        assert!(existing.is_none(), "Contract already initialized");
-
+    // Repoint minting and score updates at a new oracle (admin only)
+    pub fn set_oracle(env: Env, oracle: Address) {
+        Self::require_admin(&env);
         env.storage().instance().set(&DataKey::OracleAddress, &oracle);
+        (OracleSet { oracle }).publish(&env);
+    }
+
+    // Repoint staking at a new LoanManager (admin only)
+    pub fn set_loan_manager(env: Env, loan_manager: Address) {
+        Self::require_admin(&env);
         env.storage().instance().set(&DataKey::LoanManagerAddress, &loan_manager);
-        env.storage().instance().set(&DataKey::TokenCounter, &0u64);
+        (LoanManagerSet { loan_manager }).publish(&env);
+    }
+
+    pub fn get_config(env: Env) -> NftConfig {
+        NftConfig {
+            admin: env.storage().instance().get(&DataKey::AdminAddress).expect("Admin not configured"),
+            oracle: env.storage().instance().get(&DataKey::OracleAddress).unwrap(),
+            loan_manager: env.storage().instance().get(&DataKey::LoanManagerAddress).unwrap(),
+            soulbound: Self::is_soulbound(env.clone()),
+        }
     }
 
+    // Mint a reputation NFT for a verified remittance account. Each identity backs at most one
+    // token: re-verifying an identity refreshes its existing token instead of minting a new one.
     pub fn mint(
         env: Env,
         owner: Address,
+        identity: IdentityKey,
         monthly_amount: i128,
-        reliability_score: u32,
         history_months: u32,
         total_sent: i128,
         payment_history: Vec<PaymentRecord>
     ) -> u64 {
-        owner.require_auth();
+        // Only the oracle mints, after verifying the owner's remittance history
+        let oracle: Address = env.storage().instance().get(&DataKey::OracleAddress).unwrap();
+        oracle.require_auth();
+
+        let existing: Option<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::IdentityToken(identity.clone()));
+
+        if let Some(token_id) = existing {
+            let mut data: RemittanceData = env
+                .storage()
+                .instance()
+                .get(&DataKey::RemittanceData(token_id))
+                .expect("NFT does not exist");
+
+            // The identity is bound to whoever holds its token, so after a recovery only the
+            // new wallet can re-verify it
+            assert!(data.owner == owner, "Identity bound to another owner");
+
+            let previous_score = data.reliability_score;
+
+            // Misses already on record survive a refresh, and the history never shrinks
+            let stored: Vec<PaymentRecord> = env
+                .storage()
+                .instance()
+                .get(&DataKey::PaymentHistory(token_id))
+                .unwrap_or(Vec::new(&env));
+            let mut merged = Self::merge_payment_history(&env, &stored, &payment_history);
+            let missed = scoring::count_missed_payments(&merged);
+
+            // Keep only last 24 months
+            while merged.len() > 24 {
+                merged.remove(0);
+            }
+
+            // Staking state is preserved across refreshes
+            data.monthly_amount = monthly_amount;
+            data.history_months = data.history_months.max(history_months);
+            data.total_sent = total_sent;
+            data.last_remittance_timestamp = env.ledger().timestamp();
+            data.lifetime_missed_payments = data.lifetime_missed_payments.max(missed);
+            data.reliability_score = scoring::calculate_score(
+                &merged,
+                monthly_amount,
+                data.lifetime_missed_payments
+            );
+
+            env.storage().instance().set(&DataKey::RemittanceData(token_id), &data);
+            env.storage().instance().set(&DataKey::PaymentHistory(token_id), &merged);
+
+            Self::record_score(
+                &env,
+                token_id,
+                Some(previous_score),
+                data.reliability_score,
+                ScoreChangeReason::Refresh
+            );
+
+            (RefreshNft { owner, token_id }).publish(&env);
+
+            return token_id;
+        }
+
+        let lifetime_missed = scoring::count_missed_payments(&payment_history);
+        let reliability_score = scoring::calculate_score(
+            &payment_history,
+            monthly_amount,
+            lifetime_missed
+        );
 
         // Get and increment token counter
         let mut counter: u64 = env.storage().instance().get(&DataKey::TokenCounter).unwrap_or(0);
@@ -79,7 +325,7 @@ impl RemittanceNFT {
             history_months,
             total_sent,
             last_remittance_timestamp: env.ledger().timestamp(),
-            lifetime_missed_payments: Self::count_missed_payments(&payment_history),
+            lifetime_missed_payments: lifetime_missed,
             is_staked: false,
             staked_in_loan: 0,
         };
@@ -88,15 +334,26 @@ impl RemittanceNFT {
         env.storage().instance().set(&DataKey::TokenCounter, &counter);
         env.storage().instance().set(&DataKey::RemittanceData(counter), &data);
         env.storage().instance().set(&DataKey::PaymentHistory(counter), &payment_history);
+        env.storage().instance().set(&DataKey::IdentityToken(identity.clone()), &counter);
+        env.storage().instance().set(&DataKey::TokenIdentity(counter), &identity);
+        Self::add_to_owner_index(&env, &owner, counter);
+        Self::record_score(&env, counter, None, reliability_score, ScoreChangeReason::Mint);
 
         // Emit event
-        env.events().publish(("mint_nft", owner), counter);
+        (MintNft { owner, token_id: counter }).publish(&env);
 
         counter
     }
 
     // Stake NFT as loan collateral (called by LoanManager only)
     pub fn stake_nft(env: Env, token_id: u64, loan_id: u64) {
+        let loan_manager: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::LoanManagerAddress)
+            .unwrap();
+        loan_manager.require_auth();
+
         let mut data: RemittanceData = env
             .storage()
             .instance()
@@ -109,11 +366,18 @@ impl RemittanceNFT {
         data.staked_in_loan = loan_id;
 
         env.storage().instance().set(&DataKey::RemittanceData(token_id), &data);
-        env.events().publish(("stake_nft", token_id), loan_id);
+        (StakeNft { token_id, loan_id }).publish(&env);
     }
 
     // Unstake NFT after loan repayment (called by LoanManager only)
     pub fn unstake_nft(env: Env, token_id: u64) {
+        let loan_manager: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::LoanManagerAddress)
+            .unwrap();
+        loan_manager.require_auth();
+
         let mut data: RemittanceData = env
             .storage()
             .instance()
@@ -126,16 +390,12 @@ impl RemittanceNFT {
         data.staked_in_loan = 0;
 
         env.storage().instance().set(&DataKey::RemittanceData(token_id), &data);
-        env.events().publish(("unstake_nft",), token_id);
+        (UnstakeNft { token_id }).publish(&env);
     }
 
-    // Update remittance data (called by Oracle only)
-    pub fn update_remittance_data(
-        env: Env,
-        token_id: u64,
-        new_monthly_amount: i128,
-        new_total_sent: i128
-    ) {
+    // Record a month's remittance (called by Oracle only). `monthly_amount` stays the verified
+    // baseline so that partial remittances weigh less in the score.
+    pub fn update_remittance_data(env: Env, token_id: u64, amount: i128) {
         let oracle: Address = env.storage().instance().get(&DataKey::OracleAddress).unwrap();
         oracle.require_auth();
 
@@ -155,6 +415,7 @@ impl RemittanceNFT {
         payment_history.push_back(PaymentRecord {
             month_index: data.history_months + 1,
             paid: true,
+            amount,
         });
 
         // Keep only last 24 months
@@ -162,21 +423,29 @@ impl RemittanceNFT {
             payment_history.remove(0);
         }
 
+        let previous_score = data.reliability_score;
+
         // Update data
-        data.monthly_amount = new_monthly_amount;
-        data.total_sent = new_total_sent;
+        data.total_sent += amount;
         data.history_months += 1;
         data.last_remittance_timestamp = env.ledger().timestamp();
-        data.reliability_score = Self::calculate_score(
-            &env,
+        data.reliability_score = scoring::calculate_score(
             &payment_history,
+            data.monthly_amount,
             data.lifetime_missed_payments
         );
 
         env.storage().instance().set(&DataKey::RemittanceData(token_id), &data);
         env.storage().instance().set(&DataKey::PaymentHistory(token_id), &payment_history);
+        Self::record_score(
+            &env,
+            token_id,
+            Some(previous_score),
+            data.reliability_score,
+            ScoreChangeReason::Payment
+        );
 
-        env.events().publish(("update_nft", token_id), data.reliability_score);
+        (UpdateNft { token_id, reliability_score: data.reliability_score }).publish(&env);
     }
 
     // Mark payment as missed (called by Oracle only)
@@ -200,6 +469,7 @@ impl RemittanceNFT {
         payment_history.push_back(PaymentRecord {
             month_index: data.history_months + 1,
             paid: false,
+            amount: 0,
         });
 
         // Keep only last 24 months
@@ -207,18 +477,27 @@ impl RemittanceNFT {
             payment_history.remove(0);
         }
 
+        let previous_score = data.reliability_score;
+
         data.history_months += 1;
         data.lifetime_missed_payments += 1;
-        data.reliability_score = Self::calculate_score(
-            &env,
+        data.reliability_score = scoring::calculate_score(
             &payment_history,
+            data.monthly_amount,
             data.lifetime_missed_payments
         );
 
         env.storage().instance().set(&DataKey::RemittanceData(token_id), &data);
         env.storage().instance().set(&DataKey::PaymentHistory(token_id), &payment_history);
+        Self::record_score(
+            &env,
+            token_id,
+            Some(previous_score),
+            data.reliability_score,
+            ScoreChangeReason::Missed
+        );
 
-        env.events().publish(("payment_missed", token_id), data.reliability_score);
+        (PaymentMissed { token_id, reliability_score: data.reliability_score }).publish(&env);
     }
 
     // Get NFT data (public view)
@@ -229,6 +508,55 @@ impl RemittanceNFT {
             .expect("NFT does not exist")
     }
 
+    // Explain how the current reliability score was derived
+    pub fn get_score_breakdown(env: Env, token_id: u64) -> ScoreBreakdown {
+        let data: RemittanceData = Self::get_nft_data(env.clone(), token_id);
+        let payment_history: Vec<PaymentRecord> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PaymentHistory(token_id))
+            .unwrap_or(Vec::new(&env));
+
+        scoring::score_breakdown(&payment_history, data.monthly_amount, data.lifetime_missed_payments)
+    }
+
+    // Score snapshots, oldest first
+    pub fn get_score_history(env: Env, token_id: u64) -> Vec<ScoreSnapshot> {
+        env.storage()
+            .instance()
+            .get(&DataKey::ScoreHistory(token_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Pricing tier of a token: the number of thresholds its score has reached
+    pub fn get_tier(env: Env, token_id: u64) -> u32 {
+        let data: RemittanceData = Self::get_nft_data(env.clone(), token_id);
+        Self::tier_for_score(&env, data.reliability_score)
+    }
+
+    // Score thresholds that separate pricing tiers. Defaults mirror LoanManager's rate tiers.
+    pub fn get_tier_thresholds(env: Env) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TierThresholds)
+            .unwrap_or(Vec::from_array(&env, remitlend_math::scoring::DEFAULT_TIER_THRESHOLDS))
+    }
+
+    // Admin keeps the thresholds in sync with LoanManager's pricing table
+    pub fn set_tier_thresholds(env: Env, thresholds: Vec<u32>) {
+        Self::require_admin(&env);
+
+        for i in 1..thresholds.len() {
+            assert!(
+                thresholds.get(i - 1).unwrap() < thresholds.get(i).unwrap(),
+                "Thresholds must be ascending"
+            );
+        }
+
+        env.storage().instance().set(&DataKey::TierThresholds, &thresholds);
+        (TierThresholdsSet { thresholds }).publish(&env);
+    }
+
     // Calculate collateral value
     pub fn calculate_collateral_value(env: Env, token_id: u64, duration_months: u32) -> i128 {
         let data: RemittanceData = Self::get_nft_data(env, token_id);
@@ -236,9 +564,7 @@ impl RemittanceNFT {
         // Formula: monthly_amount × duration × (score/100) × 0.70
         let base_value = data.monthly_amount * (duration_months as i128);
         let score_adjusted = (base_value * (data.reliability_score as i128)) / 100;
-        let final_value = (score_adjusted * 70) / 100;
-
-        final_value
+        (score_adjusted * 70) / 100
     }
 
     pub fn get_token_counter(env: Env) -> u64 {
@@ -248,54 +574,224 @@ impl RemittanceNFT {
             .unwrap_or(0)
     }
 
-    // Internal: Calculate reliability score
-    fn calculate_score(
-        env: &Env,
-        payment_history: &Vec<PaymentRecord>,
-        lifetime_missed: u32
-    ) -> u32 {
-        // Count payments in last 24 months
-        let mut paid_count = 0u32;
-        let total_count = payment_history.len() as u32;
-
-        for i in 0..payment_history.len() {
-            if payment_history.get(i).unwrap().paid {
-                paid_count += 1;
-            }
+    // Collection name (NFT metadata)
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "RemitLend Reputation")
+    }
+
+    // Collection symbol (NFT metadata)
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "RMTREP")
+    }
+
+    // Metadata URI describing the token's remittance score
+    pub fn token_uri(env: Env, token_id: u64) -> String {
+        let data = Self::get_nft_data(env.clone(), token_id);
+        metadata::build_token_uri(&env, token_id, &data)
+    }
+
+    pub fn owner_of(env: Env, token_id: u64) -> Address {
+        Self::get_nft_data(env, token_id).owner
+    }
+
+    pub fn balance_of(env: Env, owner: Address) -> u32 {
+        Self::get_tokens_of(env, owner).len()
+    }
+
+    // All token ids held by an owner
+    pub fn get_tokens_of(env: Env, owner: Address) -> Vec<u64> {
+        env.storage()
+            .instance()
+            .get(&DataKey::OwnerTokens(owner))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Token backed by a verified identity, if any
+    pub fn get_token_by_identity(env: Env, identity: IdentityKey) -> Option<u64> {
+        env.storage().instance().get(&DataKey::IdentityToken(identity))
+    }
+
+    pub fn get_token_identity(env: Env, token_id: u64) -> IdentityKey {
+        env.storage()
+            .instance()
+            .get(&DataKey::TokenIdentity(token_id))
+            .expect("NFT does not exist")
+    }
+
+    // Owner approves a spender for a single token
+    pub fn approve(env: Env, owner: Address, approved: Address, token_id: u64) {
+        owner.require_auth();
+
+        let data = Self::get_nft_data(env.clone(), token_id);
+        assert!(data.owner == owner, "Not the token owner");
+
+        env.storage().instance().set(&DataKey::Approval(token_id), &approved);
+        (Approve { owner, token_id, approved }).publish(&env);
+    }
+
+    pub fn get_approved(env: Env, token_id: u64) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Approval(token_id))
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u64) {
+        from.require_auth();
+        Self::move_token(&env, &from, &to, token_id, false);
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u64) {
+        spender.require_auth();
+
+        let approved: Option<Address> = env.storage().instance().get(&DataKey::Approval(token_id));
+        assert!(spender == from || approved == Some(spender.clone()), "Spender not approved");
+
+        Self::move_token(&env, &from, &to, token_id, false);
+    }
+
+    // Admin moves a token to a new wallet (e.g. lost keys). Allowed in soulbound mode,
+    // but never while the token is staked as collateral. The token's identity moves with it.
+    pub fn recover(env: Env, token_id: u64, to: Address) {
+        Self::require_admin(&env);
+
+        let from = Self::get_nft_data(env.clone(), token_id).owner;
+        Self::move_token(&env, &from, &to, token_id, true);
+    }
+
+    // Toggle soulbound mode (admin only)
+    pub fn set_soulbound(env: Env, soulbound: bool) {
+        Self::require_admin(&env);
+
+        env.storage().instance().set(&DataKey::Soulbound, &soulbound);
+        (SoulboundSet { soulbound }).publish(&env);
+    }
+
+    pub fn is_soulbound(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Soulbound).unwrap_or(true)
+    }
+
+    // Internal: Require auth from the stored admin
+    fn require_admin(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminAddress)
+            .expect("Admin not configured");
+        admin.require_auth();
+    }
+
+    // Internal: Move a token between owners, enforcing staking and soulbound rules
+    fn move_token(env: &Env, from: &Address, to: &Address, token_id: u64, is_recovery: bool) {
+        let mut data: RemittanceData = env
+            .storage()
+            .instance()
+            .get(&DataKey::RemittanceData(token_id))
+            .expect("NFT does not exist");
+
+        assert!(data.owner == *from, "Not the token owner");
+        assert!(!data.is_staked, "NFT is staked");
+        if !is_recovery {
+            assert!(!Self::is_soulbound(env.clone()), "Token is soulbound");
         }
 
-        // Recent score (last 24 months)
-        let recent_score = if total_count > 0 { (paid_count * 100) / total_count } else { 100 };
+        data.owner = to.clone();
+        env.storage().instance().set(&DataKey::RemittanceData(token_id), &data);
+        env.storage().instance().remove(&DataKey::Approval(token_id));
 
-        // Apply lifetime penalty
-        let penalty = Self::calculate_lifetime_penalty(lifetime_missed);
+        Self::remove_from_owner_index(env, from, token_id);
+        Self::add_to_owner_index(env, to, token_id);
 
-        if recent_score > penalty {
-            recent_score - penalty
+        if is_recovery {
+            (RecoverNft { from: from.clone(), to: to.clone(), token_id }).publish(env);
         } else {
-            0
+            (Transfer { from: from.clone(), to: to.clone(), token_id }).publish(env);
         }
     }
 
-    // Internal: Calculate lifetime penalty
-    fn calculate_lifetime_penalty(lifetime_missed: u32) -> u32 {
-        match lifetime_missed {
-            0 => 0,
-            1 => 2,
-            2 => 5, // -2 for first, -3 for second
-            3 => 9, // -2, -3, -4
-            _ => 9 + (lifetime_missed - 3) * 5, // -5 for each additional
+    // Internal: Append a score snapshot and emit TierChanged when the tier moves
+    fn record_score(
+        env: &Env,
+        token_id: u64,
+        previous_score: Option<u32>,
+        score: u32,
+        reason: ScoreChangeReason
+    ) {
+        let mut history = Self::get_score_history(env.clone(), token_id);
+        history.push_back(ScoreSnapshot {
+            timestamp: env.ledger().timestamp(),
+            score,
+            reason,
+        });
+        if history.len() > MAX_SCORE_SNAPSHOTS {
+            history.remove(0);
+        }
+        env.storage().instance().set(&DataKey::ScoreHistory(token_id), &history);
+
+        if let Some(previous) = previous_score {
+            let old_tier = Self::tier_for_score(env, previous);
+            let new_tier = Self::tier_for_score(env, score);
+            if old_tier != new_tier {
+                (TierChanged { token_id, old_tier, new_tier, score }).publish(env);
+            }
         }
     }
 
-    // Internal: Count missed payments
-    fn count_missed_payments(payment_history: &Vec<PaymentRecord>) -> u32 {
-        let mut count = 0u32;
-        for i in 0..payment_history.len() {
-            if !payment_history.get(i).unwrap().paid {
-                count += 1;
+    // Internal: Count the tier thresholds reached by a score
+    fn tier_for_score(env: &Env, score: u32) -> u32 {
+        let thresholds = Self::get_tier_thresholds(env.clone());
+        remitlend_math::scoring::tier_for_score(thresholds.iter(), score)
+    }
+
+    // Internal: Record a token under its owner
+    fn add_to_owner_index(env: &Env, owner: &Address, token_id: u64) {
+        let mut tokens = Self::get_tokens_of(env.clone(), owner.clone());
+        tokens.push_back(token_id);
+        env.storage().instance().set(&DataKey::OwnerTokens(owner.clone()), &tokens);
+    }
+
+    // Internal: Drop a token from its previous owner's index
+    fn remove_from_owner_index(env: &Env, owner: &Address, token_id: u64) {
+        let mut tokens = Self::get_tokens_of(env.clone(), owner.clone());
+        if let Some(index) = tokens.first_index_of(token_id) {
+            tokens.remove(index);
+        }
+        if tokens.is_empty() {
+            env.storage().instance().remove(&DataKey::OwnerTokens(owner.clone()));
+        } else {
+            env.storage().instance().set(&DataKey::OwnerTokens(owner.clone()), &tokens);
+        }
+    }
+
+    // Internal: Merge two histories sorted by month. Where both hold the same month a miss on
+    // record wins, otherwise the newly submitted record does.
+    fn merge_payment_history(
+        env: &Env,
+        stored: &Vec<PaymentRecord>,
+        submitted: &Vec<PaymentRecord>
+    ) -> Vec<PaymentRecord> {
+        let mut merged: Vec<PaymentRecord> = Vec::new(env);
+        let (mut i, mut j) = (0u32, 0u32);
+
+        while i < stored.len() && j < submitted.len() {
+            let a = stored.get(i).unwrap();
+            let b = submitted.get(j).unwrap();
+            if a.month_index < b.month_index {
+                merged.push_back(a);
+                i += 1;
+            } else if b.month_index < a.month_index {
+                merged.push_back(b);
+                j += 1;
+            } else {
+                merged.push_back(if a.paid { b } else { a });
+                i += 1;
+                j += 1;
             }
         }
-        count
+        for k in i..stored.len() {
+            merged.push_back(stored.get(k).unwrap());
+        }
+        for k in j..submitted.len() {
+            merged.push_back(submitted.get(k).unwrap());
+        }
+
+        merged
     }
 }
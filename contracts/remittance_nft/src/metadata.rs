@@ -0,0 +1,118 @@
+use soroban_sdk::{ Env, String };
+
+use crate::RemittanceData;
+
+// Large enough for the JSON document built in `build_token_uri`
+const JSON_CAPACITY: usize = 512;
+// The base64 encoding of a full JSON document plus the `data:` prefix
+const URI_CAPACITY: usize = 720;
+
+const URI_PREFIX: &str = "data:application/json;base64,";
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Fixed-size byte writer used to assemble metadata without an allocator
+struct UriBuffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> UriBuffer<N> {
+    fn new() -> Self {
+        UriBuffer { bytes: [0u8; N], len: 0 }
+    }
+
+    fn push_byte(&mut self, b: u8) {
+        assert!(self.len < N, "Metadata too long");
+        self.bytes[self.len] = b;
+        self.len += 1;
+    }
+
+    fn push_str(&mut self, value: &str) {
+        for b in value.as_bytes() {
+            self.push_byte(*b);
+        }
+    }
+
+    // Standard base64 with padding, so the JSON's quotes, spaces and `#` survive URI parsing
+    fn push_base64(&mut self, data: &[u8]) {
+        for chunk in data.chunks(3) {
+            let b0 = chunk[0] as usize;
+            let b1 = chunk.get(1).copied().unwrap_or(0) as usize;
+            let b2 = chunk.get(2).copied().unwrap_or(0) as usize;
+            self.push_byte(BASE64_ALPHABET[b0 >> 2]);
+            self.push_byte(BASE64_ALPHABET[((b0 & 0x03) << 4) | (b1 >> 4)]);
+            if chunk.len() > 1 {
+                self.push_byte(BASE64_ALPHABET[((b1 & 0x0f) << 2) | (b2 >> 6)]);
+            } else {
+                self.push_byte(b'=');
+            }
+            if chunk.len() > 2 {
+                self.push_byte(BASE64_ALPHABET[b2 & 0x3f]);
+            } else {
+                self.push_byte(b'=');
+            }
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    fn push_u128(&mut self, mut value: u128) {
+        // u128::MAX has 39 digits
+        let mut digits = [0u8; 39];
+        let mut count = 0;
+        loop {
+            digits[count] = b'0' + ((value % 10) as u8);
+            count += 1;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        while count > 0 {
+            count -= 1;
+            self.push_byte(digits[count]);
+        }
+    }
+
+    fn push_i128(&mut self, value: i128) {
+        if value < 0 {
+            self.push_str("-");
+        }
+        self.push_u128(value.unsigned_abs());
+    }
+
+    fn push_bool(&mut self, value: bool) {
+        self.push_str(if value { "true" } else { "false" });
+    }
+
+}
+
+// Builds a base64 `data:` URI with JSON metadata describing the remittance reputation
+pub fn build_token_uri(env: &Env, token_id: u64, data: &RemittanceData) -> String {
+    let mut buf = UriBuffer::<JSON_CAPACITY>::new();
+
+    buf.push_str("{\"name\":\"RemitLend Reputation #");
+    buf.push_u128(token_id as u128);
+    buf.push_str("\",\"description\":\"Verified remittance history used as loan collateral\"");
+    buf.push_str(",\"attributes\":{\"reliability_score\":");
+    buf.push_u128(data.reliability_score as u128);
+    buf.push_str(",\"history_months\":");
+    buf.push_u128(data.history_months as u128);
+    buf.push_str(",\"monthly_amount\":");
+    buf.push_i128(data.monthly_amount);
+    buf.push_str(",\"total_sent\":");
+    buf.push_i128(data.total_sent);
+    buf.push_str(",\"lifetime_missed_payments\":");
+    buf.push_u128(data.lifetime_missed_payments as u128);
+    buf.push_str(",\"is_staked\":");
+    buf.push_bool(data.is_staked);
+    buf.push_str("}}");
+
+    let mut uri = UriBuffer::<URI_CAPACITY>::new();
+    uri.push_str(URI_PREFIX);
+    uri.push_base64(buf.as_bytes());
+    String::from_bytes(env, uri.as_bytes())
+}
@@ -0,0 +1,58 @@
+use soroban_sdk::{ contracttype, Vec };
+
+use crate::PaymentRecord;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreBreakdown {
+    pub weighted_score: u32, // 0-100, decayed and amount-weighted payment ratio
+    pub consistency_bonus: u32, // points added for the current on-time streak
+    pub lifetime_penalty: u32, // points removed for lifetime missed payments
+    pub final_score: u32, // 0-100
+    pub current_streak: u32, // consecutive paid months, most recent first
+    pub records_considered: u32,
+}
+
+// Score a payment history. Records are ordered oldest first; each one is weighted by
+// exponential decay on its age and by the amount sent relative to `expected_amount`.
+pub fn score_breakdown(
+    payment_history: &Vec<PaymentRecord>,
+    expected_amount: i128,
+    lifetime_missed: u32
+) -> ScoreBreakdown {
+    let breakdown = remitlend_math::scoring::score_breakdown(
+        payment_history
+            .iter()
+            .rev()
+            .map(|record| (record.paid, record.amount)),
+        expected_amount,
+        lifetime_missed
+    );
+
+    ScoreBreakdown {
+        weighted_score: breakdown.weighted_score,
+        consistency_bonus: breakdown.consistency_bonus,
+        lifetime_penalty: breakdown.lifetime_penalty,
+        final_score: breakdown.final_score,
+        current_streak: breakdown.current_streak,
+        records_considered: breakdown.records_considered,
+    }
+}
+
+pub fn calculate_score(
+    payment_history: &Vec<PaymentRecord>,
+    expected_amount: i128,
+    lifetime_missed: u32
+) -> u32 {
+    score_breakdown(payment_history, expected_amount, lifetime_missed).final_score
+}
+
+pub fn count_missed_payments(payment_history: &Vec<PaymentRecord>) -> u32 {
+    let mut count = 0u32;
+    for i in 0..payment_history.len() {
+        if !payment_history.get(i).unwrap().paid {
+            count += 1;
+        }
+    }
+    count
+}
@@ -0,0 +1,512 @@
+extern crate std;
+
+use base64::{ engine::general_purpose::STANDARD, Engine };
+use soroban_sdk::{
+    testutils::{ Address as _, Events as _, Ledger as _, MockAuth, MockAuthInvoke },
+    Address,
+    BytesN,
+    Env,
+    IntoVal,
+    Map,
+    String,
+    Symbol,
+    TryFromVal,
+    Val,
+    Vec,
+};
+
+use crate::{
+    IdentityKey,
+    PaymentRecord,
+    RemittanceNFT,
+    RemittanceNFTClient,
+    ScoreChangeReason,
+    MAX_SCORE_SNAPSHOTS,
+};
+
+struct Setup<'a> {
+    env: Env,
+    client: RemittanceNFTClient<'a>,
+    oracle: Address,
+    loan_manager: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let loan_manager = Address::generate(&env);
+
+    let contract_id = env.register(RemittanceNFT, (&admin, &oracle, &loan_manager));
+    let client = RemittanceNFTClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+
+    Setup { env, client, oracle, loan_manager }
+}
+
+fn identity(env: &Env, seed: u8) -> IdentityKey {
+    IdentityKey {
+        provider: String::from_str(env, "wise"),
+        account_hash: BytesN::from_array(env, &[seed; 32]),
+    }
+}
+
+fn history(env: &Env, records: &[(u32, bool)]) -> Vec<PaymentRecord> {
+    let mut history: Vec<PaymentRecord> = Vec::new(env);
+    for (month_index, paid) in records {
+        history.push_back(PaymentRecord {
+            month_index: *month_index,
+            paid: *paid,
+            amount: if *paid { 1_000 } else { 0 },
+        });
+    }
+    history
+}
+
+fn mint_as_oracle(s: &Setup, owner: &Address) -> u64 {
+    mint_identity_as_oracle(s, owner, &identity(&s.env, 1), 1_000)
+}
+
+fn mint_identity_as_oracle(
+    s: &Setup,
+    owner: &Address,
+    identity: &IdentityKey,
+    monthly_amount: i128
+) -> u64 {
+    let history = history(&s.env, &[(1, true), (2, false)]);
+    s.client
+        .mock_auths(
+            &[
+                MockAuth {
+                    address: &s.oracle,
+                    invoke: &(MockAuthInvoke {
+                        contract: &s.client.address,
+                        fn_name: "mint",
+                        args: (
+                            owner.clone(),
+                            identity.clone(),
+                            monthly_amount,
+                            12u32,
+                            12_000i128,
+                            history.clone(),
+                        ).into_val(&s.env),
+                        sub_invokes: &[],
+                    }),
+                },
+            ]
+        )
+        .mint(owner, identity, &monthly_amount, &12u32, &12_000i128, &history)
+}
+
+#[test]
+fn oracle_can_mint() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    let token_id = mint_as_oracle(&s, &owner);
+
+    assert_eq!(s.client.owner_of(&token_id), owner);
+}
+
+#[test]
+fn mint_rejects_arbitrary_caller() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let identity = identity(&s.env, 2);
+    let history: Vec<PaymentRecord> = Vec::new(&s.env);
+
+    // The owner authorizes the call, but the oracle does not
+    let result = s.client
+        .mock_auths(
+            &[
+                MockAuth {
+                    address: &owner,
+                    invoke: &(MockAuthInvoke {
+                        contract: &s.client.address,
+                        fn_name: "mint",
+                        args: (
+                            owner.clone(),
+                            identity.clone(),
+                            1_000i128,
+                            12u32,
+                            12_000i128,
+                            history.clone(),
+                        ).into_val(&s.env),
+                        sub_invokes: &[],
+                    }),
+                },
+            ]
+        )
+        .try_mint(&owner, &identity, &1_000i128, &12u32, &12_000i128, &history);
+
+    assert!(result.is_err());
+    assert_eq!(s.client.get_token_counter(), 0);
+}
+
+#[test]
+fn admin_repoints_the_oracle() {
+    let s = setup();
+    let new_oracle = Address::generate(&s.env);
+
+    let attacker = Address::generate(&s.env);
+    let result = s.client
+        .mock_auths(
+            &[
+                MockAuth {
+                    address: &attacker,
+                    invoke: &(MockAuthInvoke {
+                        contract: &s.client.address,
+                        fn_name: "set_oracle",
+                        args: (new_oracle.clone(),).into_val(&s.env),
+                        sub_invokes: &[],
+                    }),
+                },
+            ]
+        )
+        .try_set_oracle(&new_oracle);
+    assert!(result.is_err());
+    assert_eq!(s.client.get_config().oracle, s.oracle);
+
+    s.env.mock_all_auths();
+    s.client.set_oracle(&new_oracle);
+    let config = s.client.get_config();
+    assert_eq!(config.oracle, new_oracle);
+    assert_eq!(config.loan_manager, s.loan_manager);
+
+    // Minting now follows the new oracle
+    let s = Setup { oracle: new_oracle, ..s };
+    let owner = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+    assert_eq!(s.client.owner_of(&token_id), owner);
+}
+
+#[test]
+fn unstake_rejects_arbitrary_caller() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let attacker = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+
+    s.client
+        .mock_auths(
+            &[
+                MockAuth {
+                    address: &s.loan_manager,
+                    invoke: &(MockAuthInvoke {
+                        contract: &s.client.address,
+                        fn_name: "stake_nft",
+                        args: (token_id, 7u64).into_val(&s.env),
+                        sub_invokes: &[],
+                    }),
+                },
+            ]
+        )
+        .stake_nft(&token_id, &7u64);
+
+    let result = s.client
+        .mock_auths(
+            &[
+                MockAuth {
+                    address: &attacker,
+                    invoke: &(MockAuthInvoke {
+                        contract: &s.client.address,
+                        fn_name: "unstake_nft",
+                        args: (token_id,).into_val(&s.env),
+                        sub_invokes: &[],
+                    }),
+                },
+            ]
+        )
+        .try_unstake_nft(&token_id);
+
+    assert!(result.is_err());
+    assert!(s.client.get_nft_data(&token_id).is_staked);
+}
+
+#[test]
+fn token_uri_is_base64_json() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+
+    let uri = s.client.token_uri(&token_id);
+    let mut bytes = [0u8; 1024];
+    let bytes = &mut bytes[..uri.len() as usize];
+    uri.copy_into_slice(bytes);
+    let uri = core::str::from_utf8(bytes).unwrap();
+
+    let payload = uri.strip_prefix("data:application/json;base64,").expect("not a base64 data URI");
+    let json: serde_json::Value = serde_json::from_slice(&STANDARD.decode(payload).unwrap()).unwrap();
+    assert_eq!(json["name"], "RemitLend Reputation #1");
+    assert_eq!(json["attributes"]["reliability_score"], s.client.get_nft_data(&token_id).reliability_score);
+    assert_eq!(json["attributes"]["monthly_amount"], 1_000);
+    assert_eq!(json["attributes"]["lifetime_missed_payments"], 1);
+    assert_eq!(json["attributes"]["is_staked"], false);
+}
+
+#[test]
+fn soulbound_token_cannot_be_transferred() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+
+    assert!(s.client.is_soulbound());
+    assert!(s.client.try_transfer(&owner, &other, &token_id).is_err());
+    assert_eq!(s.client.owner_of(&token_id), owner);
+}
+
+#[test]
+fn transfer_moves_ownership_and_balances() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+    s.client.set_soulbound(&false);
+
+    s.client.transfer(&owner, &other, &token_id);
+
+    assert_eq!(s.client.owner_of(&token_id), other);
+    assert_eq!(s.client.balance_of(&owner), 0);
+    assert_eq!(s.client.balance_of(&other), 1);
+
+    // Only the current owner can move it on
+    assert!(s.client.try_transfer(&owner, &other, &token_id).is_err());
+}
+
+#[test]
+fn staked_token_cannot_be_transferred_or_recovered() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+    s.client.set_soulbound(&false);
+    s.client.stake_nft(&token_id, &7u64);
+
+    assert!(s.client.try_transfer(&owner, &other, &token_id).is_err());
+    assert!(s.client.try_recover(&token_id, &other).is_err());
+
+    s.client.unstake_nft(&token_id);
+    s.client.transfer(&owner, &other, &token_id);
+    assert_eq!(s.client.owner_of(&token_id), other);
+}
+
+#[test]
+fn approved_spender_can_transfer_once() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let spender = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+    s.client.set_soulbound(&false);
+
+    // Only the owner approves, and an unapproved spender is rejected
+    assert!(s.client.try_approve(&spender, &spender, &token_id).is_err());
+    assert!(s.client.try_transfer_from(&spender, &owner, &other, &token_id).is_err());
+
+    s.client.approve(&owner, &spender, &token_id);
+    assert_eq!(s.client.get_approved(&token_id), Some(spender.clone()));
+    s.client.transfer_from(&spender, &owner, &other, &token_id);
+
+    // The approval is cleared by the transfer
+    assert_eq!(s.client.owner_of(&token_id), other);
+    assert_eq!(s.client.get_approved(&token_id), None);
+    assert!(s.client.try_transfer_from(&spender, &other, &owner, &token_id).is_err());
+}
+
+#[test]
+fn admin_recovers_soulbound_token() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let new_wallet = Address::generate(&s.env);
+    let token_id = mint_as_oracle(&s, &owner);
+
+    s.client.recover(&token_id, &new_wallet);
+
+    assert_eq!(s.client.owner_of(&token_id), new_wallet);
+    assert_eq!(s.client.balance_of(&owner), 0);
+    assert_eq!(s.client.balance_of(&new_wallet), 1);
+}
+
+#[test]
+fn each_identity_backs_one_token() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    let first = mint_identity_as_oracle(&s, &owner, &identity(&s.env, 1), 1_000);
+    let second = mint_identity_as_oracle(&s, &owner, &identity(&s.env, 2), 1_000);
+
+    assert_ne!(first, second);
+    assert_eq!(s.client.get_token_by_identity(&identity(&s.env, 1)), Some(first));
+    assert_eq!(s.client.get_token_by_identity(&identity(&s.env, 2)), Some(second));
+    assert_eq!(s.client.balance_of(&owner), 2);
+}
+
+#[test]
+fn reverifying_refreshes_the_existing_token() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let token_id = mint_identity_as_oracle(&s, &owner, &identity(&s.env, 1), 1_000);
+
+    let refreshed = mint_identity_as_oracle(&s, &owner, &identity(&s.env, 1), 2_500);
+
+    assert_eq!(refreshed, token_id);
+    assert_eq!(s.client.get_token_counter(), 1);
+    assert_eq!(s.client.balance_of(&owner), 1);
+    assert_eq!(s.client.get_nft_data(&token_id).monthly_amount, 2_500);
+    assert_eq!(s.client.get_score_history(&token_id).len(), 2);
+}
+
+#[test]
+fn refresh_keeps_recorded_misses_and_history() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let identity = identity(&s.env, 1);
+    let initial = history(&s.env, &[(1, true), (2, false), (3, true)]);
+    let token_id = s.client.mint(&owner, &identity, &1_000i128, &3u32, &3_000i128, &initial);
+
+    // Month 4 is missed on-chain after the token was minted
+    s.client.mark_payment_missed(&token_id);
+
+    // The new submission is shorter, rewrites month 2 as paid and covers month 3 again
+    let submitted = history(&s.env, &[(2, true), (3, true)]);
+    s.client.mint(&owner, &identity, &1_500i128, &2u32, &3_000i128, &submitted);
+
+    let data = s.client.get_nft_data(&token_id);
+    assert_eq!(data.monthly_amount, 1_500);
+    assert_eq!(data.history_months, 4);
+    assert_eq!(data.lifetime_missed_payments, 2);
+
+    // A later submission that extends the history is merged in after the on-chain record
+    let extended = history(&s.env, &[(1, true), (2, true), (3, true), (4, true), (5, true)]);
+    s.client.mint(&owner, &identity, &1_500i128, &5u32, &5_000i128, &extended);
+
+    let data = s.client.get_nft_data(&token_id);
+    assert_eq!(data.history_months, 5);
+    assert_eq!(data.lifetime_missed_payments, 2);
+}
+
+#[test]
+fn reverifying_for_another_owner_is_rejected() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    let token_id = mint_identity_as_oracle(&s, &owner, &identity(&s.env, 1), 1_000);
+
+    let identity = identity(&s.env, 1);
+    let history: Vec<PaymentRecord> = Vec::new(&s.env);
+    let result = s.client.try_mint(
+        &other,
+        &identity,
+        &1_000i128,
+        &12u32,
+        &12_000i128,
+        &history
+    );
+
+    assert!(result.is_err());
+    assert_eq!(s.client.owner_of(&token_id), owner);
+    assert_eq!(s.client.balance_of(&other), 0);
+}
+
+#[test]
+fn recovered_token_is_reverified_by_its_new_owner() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let new_wallet = Address::generate(&s.env);
+    let token_id = mint_identity_as_oracle(&s, &owner, &identity(&s.env, 1), 1_000);
+
+    s.client.recover(&token_id, &new_wallet);
+    let refreshed = mint_identity_as_oracle(&s, &new_wallet, &identity(&s.env, 1), 2_000);
+
+    assert_eq!(refreshed, token_id);
+    assert_eq!(s.client.owner_of(&token_id), new_wallet);
+    assert_eq!(s.client.get_tokens_of(&new_wallet).len(), 1);
+    assert_eq!(s.client.balance_of(&owner), 0);
+    assert_eq!(s.client.get_nft_data(&token_id).monthly_amount, 2_000);
+
+    // The old wallet no longer speaks for the identity
+    let history: Vec<PaymentRecord> = Vec::new(&s.env);
+    let result = s.client.try_mint(
+        &owner,
+        &identity(&s.env, 1),
+        &1_000i128,
+        &12u32,
+        &12_000i128,
+        &history
+    );
+    assert!(result.is_err());
+}
+
+// TierChanged events the contract published during the last call, as (old_tier, new_tier)
+fn tier_changes(s: &Setup) -> std::vec::Vec<(u32, u32)> {
+    let name = Symbol::new(&s.env, "tier_changed");
+    s.env
+        .events()
+        .all()
+        .iter()
+        .filter(|(address, topics, _)| {
+            address == &s.client.address &&
+                topics.first().and_then(|first| Symbol::try_from_val(&s.env, &first).ok()) ==
+                    Some(name.clone())
+        })
+        .map(|(_, _, data)| {
+            let fields: Map<Symbol, Val> = Map::try_from_val(&s.env, &data).unwrap();
+            let tier = |key: &str| -> u32 {
+                u32::try_from_val(&s.env, &fields.get(Symbol::new(&s.env, key)).unwrap()).unwrap()
+            };
+            (tier("old_tier"), tier("new_tier"))
+        })
+        .collect()
+}
+
+fn mint_reliable(s: &Setup, owner: &Address) -> u64 {
+    let mut records = std::vec::Vec::new();
+    for month in 1..=12u32 {
+        records.push((month, true));
+    }
+    let history = history(&s.env, &records);
+    s.client.mint(owner, &identity(&s.env, 1), &1_000i128, &12u32, &12_000i128, &history)
+}
+
+#[test]
+fn score_history_keeps_the_latest_snapshots() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let token_id = mint_reliable(&s, &owner);
+
+    // One snapshot from the mint plus one per remittance, more than the buffer holds
+    let updates = MAX_SCORE_SNAPSHOTS + 4;
+    for month in 1..=updates {
+        s.env.ledger().set_timestamp(month as u64);
+        s.client.update_remittance_data(&token_id, &1_000i128);
+    }
+
+    let snapshots = s.client.get_score_history(&token_id);
+    assert_eq!(snapshots.len(), MAX_SCORE_SNAPSHOTS);
+
+    // The mint and the oldest remittances were dropped first
+    let oldest = snapshots.first().unwrap();
+    assert_eq!(oldest.timestamp, (updates - MAX_SCORE_SNAPSHOTS + 1) as u64);
+    assert_eq!(oldest.reason, ScoreChangeReason::Payment);
+    assert_eq!(snapshots.last().unwrap().timestamp, updates as u64);
+}
+
+#[test]
+fn tier_changed_is_emitted_only_on_tier_crossings() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let token_id = mint_reliable(&s, &owner);
+    assert_eq!(s.client.get_tier(&token_id), 3);
+
+    // A miss after a clean year drops the score into the 80s
+    s.client.mark_payment_missed(&token_id);
+    assert_eq!(tier_changes(&s), [(3, 2)]);
+    assert_eq!(s.client.get_tier(&token_id), 2);
+
+    // The next remittance moves the score within the same tier
+    s.client.update_remittance_data(&token_id, &1_000i128);
+    assert!(tier_changes(&s).is_empty());
+    assert_eq!(s.client.get_tier(&token_id), 2);
+}
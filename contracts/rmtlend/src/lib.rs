@@ -1,10 +1,385 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl};
+use soroban_sdk::{contract, contractevent, contractimpl, contracttype, Address, Env, String, Vec};
+
+#[cfg(test)]
+mod test;
+
+// The wasm build imports each protocol contract's interface; native builds (tests included)
+// link the contract crates directly so they don't need the wasm built first
+#[cfg(target_family = "wasm")]
+mod nft {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/remittance_nft.wasm"
+    );
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod nft {
+    pub use ::remittance_nft::RemittanceNFTClient as Client;
+}
+
+#[cfg(target_family = "wasm")]
+mod pool {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/lending_pool.wasm"
+    );
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod pool {
+    pub use ::lending_pool::{LendingPoolClient as Client, Tranche};
+}
+
+#[cfg(target_family = "wasm")]
+mod loan_manager {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/loan_manager.wasm"
+    );
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod loan_manager {
+    pub use ::loan_manager::{
+        ExcessPayment, LoanManagerClient as Client, LoanStatus, PaymentAllocation, PaymentFrequency,
+    };
+}
+
+#[cfg(target_family = "wasm")]
+mod oracle {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/oracle_verifier.wasm"
+    );
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod oracle {
+    pub use ::oracle_verifier::{OracleVerifierClient as Client, VerificationStatus};
+}
+
+// Addresses of the protocol contracts the router fronts
+#[contracttype]
+#[derive(Clone)]
+pub struct Registry {
+    pub remittance_nft: Address,
+    pub lending_pool: Address,
+    pub loan_manager: Address,
+    pub oracle_verifier: Address,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RouterConfig {
+    pub admin: Address,
+    pub registry: Registry,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProtocolContract {
+    RemittanceNft = 0,
+    LendingPool = 1,
+    LoanManager = 2,
+    OracleVerifier = 3,
+}
+
+// A loan request through the router, with the highest APR the borrower accepts
+#[contracttype]
+#[derive(Clone)]
+pub struct LoanRequest {
+    pub nft_id: u64,
+    pub amount: i128,
+    pub duration_months: u32,
+    pub asset: Address,
+    pub frequency: loan_manager::PaymentFrequency,
+    pub max_apr_bps: u32,
+}
+
+// Terms a loan was opened at, returned by `request_loan_with_quote`
+#[contracttype]
+#[derive(Clone)]
+pub struct LoanReceipt {
+    pub loan_id: u64,
+    pub interest_rate: u32,
+    pub apr_bps: u32,
+    pub origination_fee: i128,
+    pub installments: u32,
+    pub installment_amount: i128,
+    pub total_repayment: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RepaymentReceipt {
+    pub loan_id: u64,
+    pub amount_paid: i128,
+    pub allocation: loan_manager::PaymentAllocation,
+    pub outstanding_balance: i128,
+    pub repaid: bool,
+    pub released_nft: Option<u64>, // collateral unstaked by this payment
+}
+
+// The oracle's verification status, plus the state before a user has requested one
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerificationState {
+    NotRequested = 0,
+    Pending = 1,
+    Verified = 2,
+    Failed = 3,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Verification {
+    pub state: VerificationState,
+    pub nft_id: Option<u64>, // set once the oracle has minted or refreshed the user's NFT
+}
+
+// Everything the frontend shows for one address, in a single call
+#[contracttype]
+#[derive(Clone)]
+pub struct UserDashboard {
+    pub verified: bool,
+    pub nft_ids: Vec<u64>,
+    pub best_score: u32, // highest reliability score across the user's NFTs
+    pub loan_ids: Vec<u64>,
+    pub active_loans: u32,
+    pub total_outstanding: i128,
+    pub next_payment_due: u64, // earliest due date across active loans, 0 if none
+    pub senior_deposit: i128,
+    pub junior_deposit: i128,
+    pub pending_interest: i128,
+    pub pool_liquidity: i128,
+    pub pool_utilization: u32,
+}
+
+#[contracttype]
+pub enum DataKey {
+    AdminAddress,
+    Registry,
+}
+
+#[contractevent]
+pub struct ContractSet {
+    #[topic]
+    pub kind: ProtocolContract,
+    pub address: Address,
+}
 
 #[contract]
 pub struct RmtLend;
 
 #[contractimpl]
 impl RmtLend {
-    // Implement contract methods here
+    // Runs once at deployment
+    pub fn __constructor(env: Env, admin: Address, registry: Registry) {
+        env.storage().instance().set(&DataKey::AdminAddress, &admin);
+        env.storage().instance().set(&DataKey::Registry, &registry);
+    }
+
+    // Point the router at a redeployed protocol contract (admin only)
+    pub fn set_contract(env: Env, kind: ProtocolContract, address: Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminAddress)
+            .expect("Admin not configured");
+        admin.require_auth();
+
+        let mut registry = Self::get_registry(env.clone());
+        match kind {
+            ProtocolContract::RemittanceNft => registry.remittance_nft = address.clone(),
+            ProtocolContract::LendingPool => registry.lending_pool = address.clone(),
+            ProtocolContract::LoanManager => registry.loan_manager = address.clone(),
+            ProtocolContract::OracleVerifier => registry.oracle_verifier = address.clone(),
+        }
+        env.storage().instance().set(&DataKey::Registry, &registry);
+
+        (ContractSet { kind, address }).publish(&env);
+    }
+
+    pub fn get_registry(env: Env) -> Registry {
+        env.storage()
+            .instance()
+            .get(&DataKey::Registry)
+            .expect("Router not initialized")
+    }
+
+    pub fn get_config(env: Env) -> RouterConfig {
+        RouterConfig {
+            admin: env
+                .storage()
+                .instance()
+                .get(&DataKey::AdminAddress)
+                .expect("Admin not configured"),
+            registry: Self::get_registry(env),
+        }
+    }
+
+    // Start remittance-history verification with the oracle
+    pub fn request_verification(env: Env, user: Address, provider: String, account_id: String) {
+        user.require_auth();
+
+        let registry = Self::get_registry(env.clone());
+        oracle::Client::new(&env, &registry.oracle_verifier).request_verification(
+            &user,
+            &provider,
+            &account_id,
+        );
+    }
+
+    // Verification status together with the NFT it produced, so the frontend can wait on a
+    // single call until the token is minted
+    pub fn get_verification(env: Env, user: Address) -> Verification {
+        let registry = Self::get_registry(env.clone());
+        let oracle_client = oracle::Client::new(&env, &registry.oracle_verifier);
+
+        // Users who never asked for verification have no request on file
+        let state = match oracle_client.try_get_verification_status(&user) {
+            Ok(Ok(oracle::VerificationStatus::Pending)) => VerificationState::Pending,
+            Ok(Ok(oracle::VerificationStatus::Verified)) => VerificationState::Verified,
+            Ok(Ok(oracle::VerificationStatus::Failed)) => VerificationState::Failed,
+            _ => VerificationState::NotRequested,
+        };
+
+        Verification {
+            state,
+            nft_id: oracle_client.get_verified_token(&user),
+        }
+    }
+
+    // Quote and request a loan in one step. Fails if the quoted APR is above `max_apr_bps`,
+    // so the borrower never opens a loan at terms they did not see.
+    pub fn request_loan_with_quote(
+        env: Env,
+        borrower: Address,
+        request: LoanRequest,
+    ) -> LoanReceipt {
+        borrower.require_auth();
+
+        let registry = Self::get_registry(env.clone());
+        let loan_manager = loan_manager::Client::new(&env, &registry.loan_manager);
+
+        let quote = loan_manager.quote_loan(
+            &request.nft_id,
+            &request.amount,
+            &request.duration_months,
+            &request.asset,
+            &request.frequency,
+        );
+        assert!(
+            quote.apr_bps <= request.max_apr_bps,
+            "Quoted APR above limit"
+        );
+
+        let loan_id = loan_manager.request_loan(
+            &borrower,
+            &request.nft_id,
+            &request.amount,
+            &request.duration_months,
+            &request.asset,
+            &request.frequency,
+        );
+
+        LoanReceipt {
+            loan_id,
+            interest_rate: quote.interest_rate,
+            apr_bps: quote.apr_bps,
+            origination_fee: quote.origination_fee,
+            installments: quote.installments,
+            installment_amount: quote.installment_amount,
+            total_repayment: quote.total_repayment,
+        }
+    }
+
+    // Make a payment and report how it was allocated, and whether it closed the loan and
+    // released the collateral NFT
+    pub fn repay(
+        env: Env,
+        borrower: Address,
+        loan_id: u64,
+        amount: i128,
+        excess: loan_manager::ExcessPayment,
+    ) -> RepaymentReceipt {
+        borrower.require_auth();
+
+        let registry = Self::get_registry(env.clone());
+        let loan_manager = loan_manager::Client::new(&env, &registry.loan_manager);
+
+        let loan = loan_manager.get_loan(&loan_id);
+        assert!(
+            loan.borrower == borrower,
+            "Loan does not belong to borrower"
+        );
+
+        let allocation = loan_manager.make_payment(&loan_id, &amount, &excess);
+
+        let loan = loan_manager.get_loan(&loan_id);
+        let repaid = loan.status == loan_manager::LoanStatus::Repaid;
+
+        RepaymentReceipt {
+            loan_id,
+            amount_paid: amount,
+            allocation,
+            outstanding_balance: loan.outstanding_balance,
+            repaid,
+            released_nft: if repaid {
+                Some(loan.nft_collateral_id)
+            } else {
+                None
+            },
+        }
+    }
+
+    pub fn get_user_dashboard(env: Env, user: Address) -> UserDashboard {
+        let registry = Self::get_registry(env.clone());
+        let nft_client = nft::Client::new(&env, &registry.remittance_nft);
+        let pool_client = pool::Client::new(&env, &registry.lending_pool);
+        let loan_manager = loan_manager::Client::new(&env, &registry.loan_manager);
+
+        let verified =
+            Self::get_verification(env.clone(), user.clone()).state == VerificationState::Verified;
+
+        let nft_ids = nft_client.get_tokens_of(&user);
+        let mut best_score = 0u32;
+        for nft_id in nft_ids.iter() {
+            let score = nft_client.get_nft_data(&nft_id).reliability_score;
+            if score > best_score {
+                best_score = score;
+            }
+        }
+
+        let loan_ids = loan_manager.get_borrower_loans(&user);
+        let mut active_loans = 0u32;
+        let mut total_outstanding: i128 = 0;
+        let mut next_payment_due = 0u64;
+        for loan_id in loan_ids.iter() {
+            let loan = loan_manager.get_loan(&loan_id);
+            if loan.status == loan_manager::LoanStatus::Active {
+                active_loans += 1;
+                total_outstanding += loan.outstanding_balance;
+                if next_payment_due == 0 || loan.next_payment_due < next_payment_due {
+                    next_payment_due = loan.next_payment_due;
+                }
+            }
+        }
+
+        let senior = pool_client.get_lender_info(&user, &pool::Tranche::Senior);
+        let junior = pool_client.get_lender_info(&user, &pool::Tranche::Junior);
+
+        UserDashboard {
+            verified,
+            nft_ids,
+            best_score,
+            loan_ids,
+            active_loans,
+            total_outstanding,
+            next_payment_due,
+            senior_deposit: senior.deposit_amount,
+            junior_deposit: junior.deposit_amount,
+            pending_interest: senior.pending_interest + junior.pending_interest,
+            pool_liquidity: pool_client.get_available_liquidity(),
+            pool_utilization: pool_client.get_utilization_rate(),
+        }
+    }
 }
@@ -0,0 +1,254 @@
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, String, Vec,
+};
+
+use crate::{
+    loan_manager, nft, oracle, pool, LoanRequest, Registry, RmtLend, RmtLendClient,
+    VerificationState,
+};
+
+struct Setup<'a> {
+    env: Env,
+    router: RmtLendClient<'a>,
+    operator: Address,
+    oracle: oracle::Client<'a>,
+    nft: nft::Client<'a>,
+    pool: pool::Client<'a>,
+    loan_manager: loan_manager::Client<'a>,
+    usdc: StellarAssetClient<'a>,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let admin = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let usdc_address = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+
+    // Every contract takes the others' addresses at construction, so pick them all up front
+    let registry = Registry {
+        remittance_nft: Address::generate(&env),
+        lending_pool: Address::generate(&env),
+        loan_manager: Address::generate(&env),
+        oracle_verifier: Address::generate(&env),
+    };
+    env.register_at(
+        &registry.remittance_nft,
+        ::remittance_nft::RemittanceNFT,
+        (&admin, &registry.oracle_verifier, &registry.loan_manager),
+    );
+    env.register_at(
+        &registry.lending_pool,
+        ::lending_pool::LendingPool,
+        (&admin, &registry.loan_manager, &usdc_address, 500u32),
+    );
+    env.register_at(
+        &registry.loan_manager,
+        ::loan_manager::LoanManager,
+        (
+            &admin,
+            &registry.remittance_nft,
+            &registry.lending_pool,
+            &registry.oracle_verifier,
+            &usdc_address,
+        ),
+    );
+    env.register_at(
+        &registry.oracle_verifier,
+        ::oracle_verifier::OracleVerifier,
+        (
+            &admin,
+            &registry.remittance_nft,
+            &registry.loan_manager,
+            Vec::from_array(&env, [operator.clone()]),
+        ),
+    );
+
+    let nft = nft::Client::new(&env, &registry.remittance_nft);
+    let pool = pool::Client::new(&env, &registry.lending_pool);
+    let loan_manager = loan_manager::Client::new(&env, &registry.loan_manager);
+    let oracle = oracle::Client::new(&env, &registry.oracle_verifier);
+
+    let router = RmtLendClient::new(&env, &env.register(RmtLend, (&admin, registry)));
+
+    Setup {
+        usdc: StellarAssetClient::new(&env, &usdc_address),
+        env,
+        router,
+        operator,
+        oracle,
+        nft,
+        pool,
+        loan_manager,
+    }
+}
+
+// Verify `user` through the router and the oracle with a clean year of remittances
+fn verify(s: &Setup, user: &Address) {
+    s.router.request_verification(
+        user,
+        &String::from_str(&s.env, "wise"),
+        &String::from_str(&s.env, "acct-1"),
+    );
+
+    let mut history = Vec::new(&s.env);
+    for month_index in 1..=12u32 {
+        history.push_back(::remittance_nft::PaymentRecord {
+            month_index,
+            paid: true,
+            amount: 30_000,
+        });
+    }
+    s.oracle
+        .submit_verification(&s.operator, user, &30_000, &12, &360_000, &history);
+}
+
+#[test]
+fn verification_reports_the_minted_nft() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+
+    let before = s.router.get_verification(&user);
+    assert_eq!(before.state, VerificationState::NotRequested);
+    assert_eq!(before.nft_id, None);
+
+    s.router.request_verification(
+        &user,
+        &String::from_str(&s.env, "wise"),
+        &String::from_str(&s.env, "acct-1"),
+    );
+    let pending = s.router.get_verification(&user);
+    assert_eq!(pending.state, VerificationState::Pending);
+    assert_eq!(pending.nft_id, None);
+
+    verify(&s, &user);
+    let verified = s.router.get_verification(&user);
+    assert_eq!(verified.state, VerificationState::Verified);
+    assert_eq!(
+        verified.nft_id,
+        Some(s.nft.get_tokens_of(&user).get(0).unwrap())
+    );
+}
+
+#[test]
+fn router_quotes_repays_and_reports_a_loan() {
+    let s = setup();
+    let lender = Address::generate(&s.env);
+    s.usdc.mint(&lender, &500_000);
+    s.pool
+        .deposit(&lender, &500_000, &pool::Tranche::Senior, &0u32);
+
+    let borrower = Address::generate(&s.env);
+    verify(&s, &borrower);
+    let nft_id = s.router.get_verification(&borrower).nft_id.unwrap();
+
+    // A cap below the quoted APR opens nothing
+    let monthly = loan_manager::PaymentFrequency::Monthly;
+    let quote = s
+        .loan_manager
+        .quote_loan(&nft_id, &10_000, &6, &s.usdc.address, &monthly);
+    let mut request = LoanRequest {
+        nft_id,
+        amount: 10_000,
+        duration_months: 6,
+        asset: s.usdc.address.clone(),
+        frequency: monthly,
+        max_apr_bps: quote.apr_bps - 1,
+    };
+    assert!(s
+        .router
+        .try_request_loan_with_quote(&borrower, &request)
+        .is_err());
+    assert_eq!(s.loan_manager.get_borrower_loans(&borrower).len(), 0);
+
+    request.max_apr_bps = quote.apr_bps;
+    let receipt = s.router.request_loan_with_quote(&borrower, &request);
+    assert_eq!(receipt.apr_bps, quote.apr_bps);
+    assert_eq!(receipt.installment_amount, quote.installment_amount);
+    s.loan_manager.approve_loan(&receipt.loan_id);
+
+    let dashboard = s.router.get_user_dashboard(&borrower);
+    assert!(dashboard.verified);
+    assert_eq!(dashboard.nft_ids.len(), 1);
+    assert_eq!(dashboard.loan_ids.len(), 1);
+    assert_eq!(dashboard.active_loans, 1);
+    assert_eq!(dashboard.total_outstanding, 10_000);
+    assert_eq!(
+        dashboard.next_payment_due,
+        s.loan_manager.get_loan(&receipt.loan_id).next_payment_due
+    );
+
+    // Only the payment that closes the loan releases the collateral
+    s.usdc.mint(&borrower, &1_000);
+    let pay_ahead = loan_manager::ExcessPayment::PayAhead;
+    let first = s.router.repay(
+        &borrower,
+        &receipt.loan_id,
+        &receipt.installment_amount,
+        &pay_ahead,
+    );
+    assert_eq!(first.allocation.cycles_satisfied, 1);
+    assert!(!first.repaid);
+    assert_eq!(first.released_nft, None);
+
+    let payoff = s.loan_manager.get_payoff_amount(&receipt.loan_id);
+    let last = s.router.repay(
+        &borrower,
+        &receipt.loan_id,
+        &payoff,
+        &loan_manager::ExcessPayment::ReducePrincipal,
+    );
+    assert!(last.repaid);
+    assert_eq!(last.outstanding_balance, 0);
+    assert_eq!(last.released_nft, Some(nft_id));
+    assert!(!s.nft.get_nft_data(&nft_id).is_staked);
+
+    let dashboard = s.router.get_user_dashboard(&borrower);
+    assert_eq!(dashboard.active_loans, 0);
+    assert_eq!(dashboard.total_outstanding, 0);
+    assert_eq!(dashboard.next_payment_due, 0);
+    assert_eq!(s.router.get_user_dashboard(&lender).senior_deposit, 500_000);
+    assert_eq!(
+        TokenClient::new(&s.env, &s.usdc.address).balance(&borrower),
+        11_000 - receipt.installment_amount - payoff
+    );
+}
+
+#[test]
+fn repay_rejects_someone_elses_loan() {
+    let s = setup();
+    let lender = Address::generate(&s.env);
+    s.usdc.mint(&lender, &500_000);
+    s.pool
+        .deposit(&lender, &500_000, &pool::Tranche::Senior, &0u32);
+
+    let borrower = Address::generate(&s.env);
+    verify(&s, &borrower);
+    let nft_id = s.router.get_verification(&borrower).nft_id.unwrap();
+    let loan_id = s.loan_manager.request_loan(
+        &borrower,
+        &nft_id,
+        &10_000,
+        &6,
+        &s.usdc.address,
+        &loan_manager::PaymentFrequency::Monthly,
+    );
+    s.loan_manager.approve_loan(&loan_id);
+
+    let stranger = Address::generate(&s.env);
+    s.usdc.mint(&stranger, &1_000);
+    assert!(s
+        .router
+        .try_repay(
+            &stranger,
+            &loan_id,
+            &1_000,
+            &loan_manager::ExcessPayment::PayAhead
+        )
+        .is_err());
+}
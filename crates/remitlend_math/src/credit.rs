@@ -0,0 +1,34 @@
+use crate::BPS;
+
+// LoanManager's default credit policy: up to 3x monthly remittances, nothing below a score of
+// 50, three open loans and 40% of remittance income in installments
+pub const INCOME_MULTIPLE_BPS: u32 = 30000;
+pub const MIN_SCORE: u32 = 50;
+pub const MAX_ACTIVE_LOANS: u32 = 3;
+pub const MAX_DEBT_TO_INCOME_BPS: u32 = 4000;
+
+// Consecutive missed payments before a loan defaults and is written off
+pub const MISSES_TO_DEFAULT: u32 = 2;
+
+// Total principal a borrower may owe: `income_multiple_bps` of monthly remittances at a
+// perfect score, scaled down linearly with the score, and nothing below `min_score`
+pub fn credit_limit(
+    income_multiple_bps: u32,
+    min_score: u32,
+    monthly_amount: i128,
+    score: u32,
+) -> i128 {
+    if score < min_score || monthly_amount <= 0 {
+        return 0;
+    }
+    let at_full_score = (monthly_amount * (income_multiple_bps as i128)) / BPS;
+    (at_full_score * (core::cmp::min(score, 100) as i128)) / 100
+}
+
+// Monthly installments as a share of monthly remittance income, in basis points
+pub fn debt_to_income_bps(monthly_debt: i128, monthly_amount: i128) -> i128 {
+    if monthly_amount <= 0 {
+        return i128::MAX;
+    }
+    (monthly_debt * BPS) / monthly_amount
+}
@@ -0,0 +1,14 @@
+//! Integer math behind loan pricing, amortization, credit limits and reputation scoring.
+//!
+//! `no_std` and free of soroban types so the contracts and the off-chain simulator run the
+//! exact same functions. The contracts wrap these in their own `contracttype` structs.
+#![no_std]
+
+pub mod credit;
+pub mod pricing;
+pub mod scoring;
+
+#[cfg(test)]
+mod test;
+
+pub const BPS: i128 = 10_000;
@@ -0,0 +1,181 @@
+use crate::BPS;
+
+// Fixed-point scale used when solving for the periodic rate behind an APR
+const RATE_SCALE: i128 = 1_000_000_000;
+const APR_SOLVER_ITERATIONS: u32 = 40;
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+pub const SECONDS_PER_MONTH: u64 = 30 * SECONDS_PER_DAY;
+pub const MONTHS_PER_YEAR: u32 = 12;
+
+// Legacy tiers (90+: 15%, 80+: 20%, 70+: 30%, else 40%) as (min_score, rate_bps)
+pub const DEFAULT_SCORE_BANDS: [(u32, u32); 4] = [(0, 4000), (70, 3000), (80, 2000), (90, 1500)];
+pub const DEFAULT_UTILIZATION_KINK_BPS: u32 = 8000;
+pub const DEFAULT_MAX_RATE_BPS: u32 = 10000;
+
+// Value of the last band whose threshold is at or below `key`; bands are ascending
+pub fn band_value<K: PartialOrd>(bands: impl IntoIterator<Item = (K, u32)>, key: K) -> u32 {
+    let mut value = 0u32;
+    for (threshold, band_value) in bands {
+        if key >= threshold {
+            value = band_value;
+        }
+    }
+    value
+}
+
+// Premium that grows linearly from zero at the kink to `premium_bps` at 100% utilization
+pub fn utilization_premium(kink_bps: u32, premium_bps: u32, utilization_bps: u32) -> u32 {
+    if utilization_bps <= kink_bps {
+        return 0;
+    }
+    let excess = (core::cmp::min(utilization_bps, 10000) - kink_bps) as u64;
+    let span = (10000 - kink_bps) as u64;
+    (((premium_bps as u64) * excess) / span) as u32
+}
+
+// Base rate plus premiums, capped at `max_rate_bps`
+pub fn capped_rate(base_rate_bps: u32, premiums_bps: &[u32], max_rate_bps: u32) -> u32 {
+    let mut rate = base_rate_bps;
+    for premium in premiums_bps {
+        rate += premium;
+    }
+    core::cmp::min(rate, max_rate_bps)
+}
+
+// Number of installments that cover a term of `months`, rounded up to a whole cycle
+pub fn installments_for(months: u32, periods_per_year: u32) -> u32 {
+    (months * periods_per_year).div_ceil(MONTHS_PER_YEAR)
+}
+
+// Flat installment: simple interest over the term, spread evenly across the cycles
+pub fn installment(
+    principal: i128,
+    annual_rate_bps: u32,
+    periods: u32,
+    periods_per_year: u32,
+) -> i128 {
+    let total_interest = total_interest(principal, annual_rate_bps, periods, periods_per_year);
+    (principal + total_interest) / (periods as i128)
+}
+
+pub fn total_interest(
+    principal: i128,
+    annual_rate_bps: u32,
+    periods: u32,
+    periods_per_year: u32,
+) -> i128 {
+    (principal * (annual_rate_bps as i128) * (periods as i128)) / ((periods_per_year as i128) * BPS)
+}
+
+// Interest accrued on the outstanding balance for one cycle
+pub fn interest_portion(outstanding: i128, annual_rate_bps: u32, periods_per_year: u32) -> i128 {
+    (outstanding * (annual_rate_bps as i128)) / ((periods_per_year as i128) * BPS)
+}
+
+// Installments scaled to a monthly figure, for comparison with monthly remittance income
+pub fn monthly_equivalent(installment: i128, periods_per_year: u32) -> i128 {
+    (installment * (periods_per_year as i128)) / (MONTHS_PER_YEAR as i128)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Installment {
+    pub installment: u32, // 1-based
+    pub payment: i128,
+    pub principal: i128,
+    pub interest: i128,
+    pub remaining_balance: i128,
+}
+
+// The installments `make_payment` applies: interest on the outstanding balance first, the
+// rest to principal, until the balance is cleared
+pub fn schedule(
+    principal: i128,
+    annual_rate_bps: u32,
+    periods: u32,
+    periods_per_year: u32,
+) -> Schedule {
+    Schedule {
+        installment: installment(principal, annual_rate_bps, periods, periods_per_year),
+        annual_rate_bps,
+        periods,
+        periods_per_year,
+        balance: principal,
+        index: 0,
+    }
+}
+
+#[derive(Clone)]
+pub struct Schedule {
+    installment: i128,
+    annual_rate_bps: u32,
+    periods: u32,
+    periods_per_year: u32,
+    balance: i128,
+    index: u32,
+}
+
+impl Iterator for Schedule {
+    type Item = Installment;
+
+    fn next(&mut self) -> Option<Installment> {
+        if self.balance <= 0 || self.index >= self.periods {
+            return None;
+        }
+        self.index += 1;
+        let interest = interest_portion(self.balance, self.annual_rate_bps, self.periods_per_year);
+
+        // The final installment settles whatever is left
+        let payment = if self.index == self.periods || self.installment >= self.balance + interest {
+            self.balance + interest
+        } else {
+            self.installment
+        };
+        let principal = if payment > interest {
+            payment - interest
+        } else {
+            0
+        };
+        self.balance -= principal;
+
+        Some(Installment {
+            installment: self.index,
+            payment,
+            principal,
+            interest,
+            remaining_balance: self.balance,
+        })
+    }
+}
+
+// Annual percentage rate (periodic IRR x cycles per year) that discounts the payments back
+// to `amount_financed`. Solved by bisection on the periodic rate.
+pub fn apr_bps(amount_financed: i128, payments: &[i128], periods_per_year: u32) -> u32 {
+    let total: i128 = payments.iter().sum();
+    if amount_financed <= 0 || total <= amount_financed {
+        return 0;
+    }
+
+    let mut low: i128 = 0;
+    let mut high: i128 = RATE_SCALE; // 100% per cycle
+    for _ in 0..APR_SOLVER_ITERATIONS {
+        let mid = (low + high) / 2;
+        if present_value(payments, mid) > amount_financed {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    ((high * (periods_per_year as i128) * BPS) / RATE_SCALE) as u32
+}
+
+// Present value of one payment per cycle at a periodic rate scaled by RATE_SCALE
+fn present_value(payments: &[i128], periodic_rate: i128) -> i128 {
+    let mut discount = RATE_SCALE;
+    let mut pv: i128 = 0;
+    for payment in payments.iter().copied() {
+        discount = (discount * RATE_SCALE) / (RATE_SCALE + periodic_rate);
+        pv += (payment * discount) / RATE_SCALE;
+    }
+    pv
+}
@@ -0,0 +1,109 @@
+use crate::BPS;
+
+// Weight kept per month of age (bps): a payment one month older counts 90% as much
+pub const DECAY_PER_MONTH_BPS: i128 = 9_000;
+// Consecutive on-time months needed for each bonus point
+pub const STREAK_MONTHS_PER_POINT: u32 = 3;
+pub const MAX_CONSISTENCY_BONUS: u32 = 5;
+// Scores at which a token moves up a tier, matching the default score bands
+pub const DEFAULT_TIER_THRESHOLDS: [u32; 3] = [70, 80, 90];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Breakdown {
+    pub weighted_score: u32, // 0-100, decayed and amount-weighted payment ratio
+    pub consistency_bonus: u32, // points added for the current on-time streak
+    pub lifetime_penalty: u32, // points removed for lifetime missed payments
+    pub final_score: u32,    // 0-100
+    pub current_streak: u32, // consecutive paid months, most recent first
+    pub records_considered: u32,
+}
+
+// Score a payment history given as (paid, amount) records, newest first. Each record is
+// weighted by exponential decay on its age and by the amount sent relative to
+// `expected_amount`.
+pub fn score_breakdown(
+    newest_first: impl IntoIterator<Item = (bool, i128)>,
+    expected_amount: i128,
+    lifetime_missed: u32,
+) -> Breakdown {
+    let mut weight = BPS;
+    let mut weighted_paid: i128 = 0;
+    let mut total_weight: i128 = 0;
+    let mut current_streak = 0u32;
+    let mut streak_open = true;
+    let mut records = 0u32;
+
+    for (paid, amount) in newest_first {
+        records += 1;
+
+        total_weight += weight;
+        if paid {
+            weighted_paid += (weight * amount_factor_bps(amount, expected_amount)) / BPS;
+        }
+
+        if streak_open && paid {
+            current_streak += 1;
+        } else {
+            streak_open = false;
+        }
+
+        weight = (weight * DECAY_PER_MONTH_BPS) / BPS;
+    }
+
+    let weighted_score = if total_weight > 0 {
+        ((weighted_paid * 100) / total_weight) as u32
+    } else {
+        100
+    };
+
+    let consistency_bonus = core::cmp::min(
+        current_streak / STREAK_MONTHS_PER_POINT,
+        MAX_CONSISTENCY_BONUS,
+    );
+    let lifetime_penalty = lifetime_penalty(lifetime_missed);
+
+    let boosted = core::cmp::min(weighted_score + consistency_bonus, 100);
+    let final_score = boosted.saturating_sub(lifetime_penalty);
+
+    Breakdown {
+        weighted_score,
+        consistency_bonus,
+        lifetime_penalty,
+        final_score,
+        current_streak,
+        records_considered: records,
+    }
+}
+
+// Share of the expected amount that was actually sent, capped at 100% (bps)
+pub fn amount_factor_bps(amount: i128, expected_amount: i128) -> i128 {
+    if expected_amount <= 0 {
+        return BPS;
+    }
+    if amount <= 0 {
+        return 0;
+    }
+    core::cmp::min((amount * BPS) / expected_amount, BPS)
+}
+
+// Points removed for missed payments over the token's lifetime
+pub fn lifetime_penalty(lifetime_missed: u32) -> u32 {
+    match lifetime_missed {
+        0 => 0,
+        1 => 2,
+        2 => 5,                             // -2 for first, -3 for second
+        3 => 9,                             // -2, -3, -4
+        _ => 9 + (lifetime_missed - 3) * 5, // -5 for each additional
+    }
+}
+
+// Index of the tier a score falls in, given ascending thresholds (0 below the first)
+pub fn tier_for_score(thresholds: impl IntoIterator<Item = u32>, score: u32) -> u32 {
+    let mut tier = 0u32;
+    for threshold in thresholds {
+        if score >= threshold {
+            tier += 1;
+        }
+    }
+    tier
+}
@@ -0,0 +1,35 @@
+use crate::pricing::apr_bps;
+
+const USDC: i128 = 10_000_000;
+
+// 1,000 USDC repaid in equal installments at a 10.4% nominal annual rate. The installments are
+// worked out by hand from P * r / (1 - (1 + r)^-n), so the APR must come back as 10.4%.
+#[test]
+fn weekly_apr_matches_nominal_rate() {
+    // r = 10.4% / 52 = 0.2% per week, n = 52
+    let payments = [202_673_065i128; 52];
+    let apr = apr_bps(1_000 * USDC, &payments, 52);
+    assert!((1039..=1040).contains(&apr), "weekly APR {}", apr);
+}
+
+#[test]
+fn bi_weekly_apr_matches_nominal_rate() {
+    // r = 10.4% / 26 = 0.4% per fortnight, n = 26
+    let payments = [405_730_017i128; 26];
+    let apr = apr_bps(1_000 * USDC, &payments, 26);
+    assert!((1039..=1040).contains(&apr), "bi-weekly APR {}", apr);
+}
+
+// The same cash flows read at the wrong frequency annualize to a different APR
+#[test]
+fn apr_annualizes_by_frequency() {
+    let payments = [405_730_017i128; 26];
+    let monthly = apr_bps(1_000 * USDC, &payments, 12);
+    assert!((479..=480).contains(&monthly), "monthly APR {}", monthly);
+}
+
+#[test]
+fn no_finance_charge_means_zero_apr() {
+    assert_eq!(apr_bps(1_000 * USDC, &[500 * USDC, 500 * USDC], 26), 0);
+    assert_eq!(apr_bps(0, &[500 * USDC], 52), 0);
+}
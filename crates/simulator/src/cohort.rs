@@ -0,0 +1,98 @@
+use rand::Rng;
+
+use crate::portfolio::USDC;
+
+// A population of borrowers with similar remittance behaviour
+pub struct Cohort {
+    pub share: f64,                  // fraction of all borrowers
+    pub on_time: f64,                // probability of paying in any given month
+    pub monthly_range: (i128, i128), // monthly remittance, whole USDC
+    pub shortfall: f64,              // probability a paid month sends less than usual
+}
+
+pub const COHORTS: [Cohort; 3] = [
+    // Steady senders
+    Cohort {
+        share: 0.5,
+        on_time: 0.97,
+        monthly_range: (600, 1_500),
+        shortfall: 0.05,
+    },
+    // Irregular senders
+    Cohort {
+        share: 0.35,
+        on_time: 0.85,
+        monthly_range: (300, 1_000),
+        shortfall: 0.2,
+    },
+    // Financially stressed senders
+    Cohort {
+        share: 0.15,
+        on_time: 0.65,
+        monthly_range: (200, 700),
+        shortfall: 0.4,
+    },
+];
+
+// Months of remittance history the oracle has verified before a borrower first applies
+pub const HISTORY_MONTHS: u32 = 12;
+
+pub struct Borrower {
+    pub cohort: usize,
+    pub monthly_amount: i128,
+    // (paid, amount) per month, oldest first, as the NFT stores it
+    pub history: Vec<(bool, i128)>,
+    pub lifetime_missed: u32, // missed loan payments, as reported to the NFT
+}
+
+impl Borrower {
+    pub fn generate(rng: &mut impl Rng) -> Self {
+        let cohort = pick_cohort(rng.gen());
+        let (low, high) = COHORTS[cohort].monthly_range;
+        let mut borrower = Borrower {
+            cohort,
+            monthly_amount: rng.gen_range(low..=high) * USDC,
+            history: Vec::new(),
+            lifetime_missed: 0,
+        };
+        for _ in 0..HISTORY_MONTHS {
+            borrower.remit(rng);
+        }
+        borrower
+    }
+
+    // One month of remittances; returns whether the borrower paid
+    pub fn remit(&mut self, rng: &mut impl Rng) -> bool {
+        let cohort = &COHORTS[self.cohort];
+        let paid = rng.gen_bool(cohort.on_time);
+        let amount = if !paid {
+            0
+        } else if rng.gen_bool(cohort.shortfall) {
+            (self.monthly_amount * rng.gen_range(40..100)) / 100
+        } else {
+            self.monthly_amount
+        };
+        self.history.push((paid, amount));
+        paid
+    }
+
+    pub fn score(&self) -> u32 {
+        remitlend_math::scoring::score_breakdown(
+            self.history.iter().rev().copied(),
+            self.monthly_amount,
+            self.lifetime_missed,
+        )
+        .final_score
+    }
+}
+
+fn pick_cohort(draw: f64) -> usize {
+    let mut cumulative = 0.0;
+    for (i, cohort) in COHORTS.iter().enumerate() {
+        cumulative += cohort.share;
+        if draw < cumulative {
+            return i;
+        }
+    }
+    COHORTS.len() - 1
+}
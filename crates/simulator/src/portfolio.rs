@@ -0,0 +1,229 @@
+use rand::Rng;
+
+use remitlend_math::credit;
+use remitlend_math::pricing;
+use remitlend_math::scoring;
+
+use crate::cohort::Borrower;
+
+pub const USDC: i128 = 10_000_000;
+
+const DURATIONS: [u32; 3] = [3, 6, 12];
+
+pub struct Settings {
+    pub borrowers: u32,
+    pub months: u32,
+    pub capital: i128, // lender deposits at the start of the run
+    pub utilization_premium_bps: u32,
+    pub apply_probability: f64, // chance a borrower without a loan applies in a month
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            borrowers: 500,
+            months: 24,
+            capital: 250_000 * USDC,
+            utilization_premium_bps: 0,
+            apply_probability: 0.25,
+        }
+    }
+}
+
+struct Loan {
+    borrower: usize,
+    tier: u32,
+    outstanding: i128,
+    interest_rate: u32,
+    installment: i128,
+    payments_missed: u32,
+}
+
+// Totals for one month of one run
+#[derive(Clone, Copy, Default)]
+pub struct MonthStats {
+    pub utilization_bps: i128,
+    pub interest_earned: i128,
+    pub losses: i128,
+    pub originations: u32,
+    pub defaults: u32,
+    pub active_loans: u32,
+}
+
+// Per-tier totals for one run
+#[derive(Clone, Copy, Default)]
+pub struct TierStats {
+    pub loans: u32,
+    pub defaults: u32,
+    pub principal: i128,
+    pub rate_bps_sum: u64,
+    pub declined: u32,
+}
+
+pub struct Outcome {
+    pub capital: i128,
+    pub months: Vec<MonthStats>,
+    pub tiers: Vec<TierStats>,
+}
+
+pub struct Portfolio<'a> {
+    settings: &'a Settings,
+    borrowers: Vec<Borrower>,
+    loans: Vec<Loan>,
+    has_loan: Vec<bool>,
+    liquidity: i128,
+    outstanding: i128,
+}
+
+impl<'a> Portfolio<'a> {
+    pub fn new(settings: &'a Settings, rng: &mut impl Rng) -> Self {
+        let borrowers: Vec<Borrower> = (0..settings.borrowers)
+            .map(|_| Borrower::generate(rng))
+            .collect();
+        Portfolio {
+            settings,
+            has_loan: vec![false; borrowers.len()],
+            borrowers,
+            loans: Vec::new(),
+            liquidity: settings.capital,
+            outstanding: 0,
+        }
+    }
+
+    pub fn run(mut self, rng: &mut impl Rng) -> Outcome {
+        let mut outcome = Outcome {
+            capital: self.settings.capital,
+            months: Vec::with_capacity(self.settings.months as usize),
+            tiers: vec![TierStats::default(); scoring::DEFAULT_TIER_THRESHOLDS.len() + 1],
+        };
+
+        for _ in 0..self.settings.months {
+            let mut month = MonthStats::default();
+            self.originate(rng, &mut month, &mut outcome.tiers);
+            self.collect(rng, &mut month, &mut outcome.tiers);
+
+            month.active_loans = self.loans.len() as u32;
+            let deployed = self.outstanding + self.liquidity;
+            if deployed > 0 {
+                month.utilization_bps = (self.outstanding * remitlend_math::BPS) / deployed;
+            }
+            outcome.months.push(month);
+        }
+        outcome
+    }
+
+    // Borrowers without a loan apply; requests go through the same limit, debt-to-income and
+    // pricing rules as `request_loan`
+    fn originate(&mut self, rng: &mut impl Rng, month: &mut MonthStats, tiers: &mut [TierStats]) {
+        for index in 0..self.borrowers.len() {
+            if self.has_loan[index] || !rng.gen_bool(self.settings.apply_probability) {
+                continue;
+            }
+            let borrower = &self.borrowers[index];
+            let score = borrower.score();
+            let tier = scoring::tier_for_score(scoring::DEFAULT_TIER_THRESHOLDS, score);
+
+            let months = DURATIONS[rng.gen_range(0..DURATIONS.len())];
+            let wanted = (borrower.monthly_amount * rng.gen_range(50..300)) / 100;
+            let limit = credit::credit_limit(
+                credit::INCOME_MULTIPLE_BPS,
+                credit::MIN_SCORE,
+                borrower.monthly_amount,
+                score,
+            );
+            let amount = wanted.min(limit);
+
+            let utilization_bps = self.utilization_after(amount);
+            let rate = pricing::capped_rate(
+                pricing::band_value(pricing::DEFAULT_SCORE_BANDS, score),
+                &[pricing::utilization_premium(
+                    pricing::DEFAULT_UTILIZATION_KINK_BPS,
+                    self.settings.utilization_premium_bps,
+                    utilization_bps,
+                )],
+                pricing::DEFAULT_MAX_RATE_BPS,
+            );
+            let installment = pricing::installment(amount, rate, months, pricing::MONTHS_PER_YEAR);
+
+            let affordable = amount > 0
+                && credit::debt_to_income_bps(installment, borrower.monthly_amount)
+                    <= (credit::MAX_DEBT_TO_INCOME_BPS as i128);
+            if !affordable || amount > self.liquidity {
+                tiers[tier as usize].declined += 1;
+                continue;
+            }
+
+            self.liquidity -= amount;
+            self.outstanding += amount;
+            self.has_loan[index] = true;
+            self.loans.push(Loan {
+                borrower: index,
+                tier,
+                outstanding: amount,
+                interest_rate: rate,
+                installment,
+                payments_missed: 0,
+            });
+
+            month.originations += 1;
+            let stats = &mut tiers[tier as usize];
+            stats.loans += 1;
+            stats.principal += amount;
+            stats.rate_bps_sum += rate as u64;
+        }
+    }
+
+    // Every borrower remits once a month. Those who remit pay their installment, split into
+    // interest and principal as `make_payment` does; the rest miss it.
+    fn collect(&mut self, rng: &mut impl Rng, month: &mut MonthStats, tiers: &mut [TierStats]) {
+        let mut paying = vec![false; self.borrowers.len()];
+        for (index, borrower) in self.borrowers.iter_mut().enumerate() {
+            paying[index] = borrower.remit(rng);
+        }
+
+        let mut still_open = Vec::with_capacity(self.loans.len());
+        for mut loan in self.loans.drain(..) {
+            if paying[loan.borrower] {
+                let interest_due = pricing::interest_portion(
+                    loan.outstanding,
+                    loan.interest_rate,
+                    pricing::MONTHS_PER_YEAR,
+                );
+                let amount = loan.installment.min(loan.outstanding + interest_due);
+                let interest = amount.min(interest_due);
+                let principal = amount - interest;
+
+                loan.outstanding -= principal;
+                self.outstanding -= principal;
+                self.liquidity += amount;
+                month.interest_earned += interest;
+            } else {
+                loan.payments_missed += 1;
+                self.borrowers[loan.borrower].lifetime_missed += 1;
+                if loan.payments_missed >= credit::MISSES_TO_DEFAULT {
+                    self.outstanding -= loan.outstanding;
+                    month.losses += loan.outstanding;
+                    month.defaults += 1;
+                    tiers[loan.tier as usize].defaults += 1;
+                    self.has_loan[loan.borrower] = false;
+                    continue;
+                }
+            }
+
+            if loan.outstanding <= 0 {
+                self.has_loan[loan.borrower] = false;
+            } else {
+                still_open.push(loan);
+            }
+        }
+        self.loans = still_open;
+    }
+
+    fn utilization_after(&self, amount: i128) -> u32 {
+        let deployed = self.outstanding + self.liquidity;
+        if deployed <= 0 {
+            return 0;
+        }
+        (((self.outstanding + amount) * remitlend_math::BPS) / deployed) as u32
+    }
+}
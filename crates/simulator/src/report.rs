@@ -0,0 +1,139 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use remitlend_math::scoring::DEFAULT_TIER_THRESHOLDS;
+
+use crate::portfolio::{MonthStats, Outcome, TierStats, USDC};
+
+// Sums across runs; written out as per-run means
+pub struct Report {
+    runs: u32,
+    capital: i128,
+    months: Vec<MonthStats>,
+    tiers: Vec<TierStats>,
+}
+
+impl Report {
+    pub fn new(months: u32) -> Self {
+        Report {
+            runs: 0,
+            capital: 0,
+            months: vec![MonthStats::default(); months as usize],
+            tiers: vec![TierStats::default(); DEFAULT_TIER_THRESHOLDS.len() + 1],
+        }
+    }
+
+    pub fn add(&mut self, outcome: &Outcome) {
+        self.runs += 1;
+        self.capital += outcome.capital;
+        for (total, month) in self.months.iter_mut().zip(&outcome.months) {
+            total.utilization_bps += month.utilization_bps;
+            total.interest_earned += month.interest_earned;
+            total.losses += month.losses;
+            total.originations += month.originations;
+            total.defaults += month.defaults;
+            total.active_loans += month.active_loans;
+        }
+        for (total, tier) in self.tiers.iter_mut().zip(&outcome.tiers) {
+            total.loans += tier.loans;
+            total.defaults += tier.defaults;
+            total.principal += tier.principal;
+            total.rate_bps_sum += tier.rate_bps_sum;
+            total.declined += tier.declined;
+        }
+    }
+
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        self.write_monthly(&dir.join("monthly.csv"))?;
+        self.write_tiers(&dir.join("tiers.csv"))
+    }
+
+    // Yields are annualized on the starting capital; net yield is after write-offs
+    fn write_monthly(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(
+            out,
+            "month,utilization_pct,gross_yield_pct,net_yield_pct,originations,defaults,losses_usdc,active_loans,cumulative_default_rate_pct"
+        )?;
+
+        let runs = self.runs as f64;
+        let mut originated = 0u32;
+        let mut defaulted = 0u32;
+        for (i, month) in self.months.iter().enumerate() {
+            originated += month.originations;
+            defaulted += month.defaults;
+            writeln!(
+                out,
+                "{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+                i + 1,
+                (month.utilization_bps as f64) / runs / 100.0,
+                annualized_pct(month.interest_earned, self.capital),
+                annualized_pct(month.interest_earned - month.losses, self.capital),
+                (month.originations as f64) / runs,
+                (month.defaults as f64) / runs,
+                usdc(month.losses) / runs,
+                (month.active_loans as f64) / runs,
+                pct(defaulted, originated)
+            )?;
+        }
+        out.flush()
+    }
+
+    fn write_tiers(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(
+            out,
+            "tier,min_score,loans,declined,defaults,default_rate_pct,avg_rate_pct,avg_principal_usdc"
+        )?;
+
+        let runs = self.runs as f64;
+        for (tier, stats) in self.tiers.iter().enumerate() {
+            let min_score = if tier == 0 {
+                0
+            } else {
+                DEFAULT_TIER_THRESHOLDS[tier - 1]
+            };
+            let (avg_rate, avg_principal) = if stats.loans > 0 {
+                (
+                    (stats.rate_bps_sum as f64) / (stats.loans as f64) / 100.0,
+                    usdc(stats.principal) / (stats.loans as f64),
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            writeln!(
+                out,
+                "{},{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+                tier,
+                min_score,
+                (stats.loans as f64) / runs,
+                (stats.declined as f64) / runs,
+                (stats.defaults as f64) / runs,
+                pct(stats.defaults, stats.loans),
+                avg_rate,
+                avg_principal
+            )?;
+        }
+        out.flush()
+    }
+}
+
+fn annualized_pct(amount: i128, capital: i128) -> f64 {
+    if capital == 0 {
+        return 0.0;
+    }
+    ((amount as f64) / (capital as f64)) * 12.0 * 100.0
+}
+
+fn pct(part: u32, whole: u32) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    ((part as f64) / (whole as f64)) * 100.0
+}
+
+fn usdc(amount: i128) -> f64 {
+    (amount as f64) / (USDC as f64)
+}
@@ -0,0 +1,89 @@
+use std::fs;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use remitlend_math::scoring::DEFAULT_TIER_THRESHOLDS;
+
+use crate::portfolio::{Outcome, Portfolio, Settings};
+use crate::report::Report;
+
+fn settings() -> Settings {
+    Settings {
+        borrowers: 200,
+        months: 12,
+        ..Settings::default()
+    }
+}
+
+fn run(settings: &Settings, seed: u64) -> Outcome {
+    let mut rng = StdRng::seed_from_u64(seed);
+    Portfolio::new(settings, &mut rng).run(&mut rng)
+}
+
+#[test]
+fn seeded_runs_keep_report_invariants() {
+    let settings = settings();
+    let mut report = Report::new(settings.months);
+
+    for seed in 0..5 {
+        let outcome = run(&settings, seed);
+        assert_eq!(outcome.months.len(), settings.months as usize);
+        assert_eq!(outcome.tiers.len(), DEFAULT_TIER_THRESHOLDS.len() + 1);
+
+        let mut originated = 0u32;
+        let mut defaulted = 0u32;
+        for month in &outcome.months {
+            assert!((0..=remitlend_math::BPS).contains(&month.utilization_bps));
+            assert!(month.interest_earned >= 0);
+            assert!(month.losses >= 0);
+            assert_eq!(month.losses > 0, month.defaults > 0);
+
+            // Only loans that were originated can default
+            originated += month.originations;
+            defaulted += month.defaults;
+            assert!(defaulted <= originated);
+            assert!(month.active_loans <= settings.borrowers);
+        }
+        assert!(originated > 0, "seed {} originated nothing", seed);
+
+        // Every origination and default lands in exactly one tier
+        let tier_loans: u32 = outcome.tiers.iter().map(|tier| tier.loans).sum();
+        let tier_defaults: u32 = outcome.tiers.iter().map(|tier| tier.defaults).sum();
+        assert_eq!(tier_loans, originated);
+        assert_eq!(tier_defaults, defaulted);
+
+        report.add(&outcome);
+    }
+
+    let dir = std::env::temp_dir().join(format!("remitlend-simulator-{}", std::process::id()));
+    report.write(&dir).unwrap();
+    let monthly = fs::read_to_string(dir.join("monthly.csv")).unwrap();
+    let tiers = fs::read_to_string(dir.join("tiers.csv")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    // A header plus one row per month and per tier, each with as many fields as the header
+    for (csv, rows) in [
+        (monthly, settings.months as usize),
+        (tiers, DEFAULT_TIER_THRESHOLDS.len() + 1),
+    ] {
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), rows + 1);
+        let fields = lines[0].split(',').count();
+        assert!(lines.iter().all(|line| line.split(',').count() == fields));
+    }
+}
+
+#[test]
+fn same_seed_gives_same_outcome() {
+    let settings = settings();
+    let first = run(&settings, 7);
+    let second = run(&settings, 7);
+
+    for (a, b) in first.months.iter().zip(&second.months) {
+        assert_eq!(
+            (a.originations, a.defaults, a.interest_earned, a.losses),
+            (b.originations, b.defaults, b.interest_earned, b.losses)
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Registers `RemittanceNFT`, `LendingPool`, `LoanManager` and `OracleVerifier` in one
+//! soroban `Env`, with a Stellar Asset Contract standing in for USDC, and wraps the
+//! multi-step flows between them so tests can script whole loan lifecycles.
+
+use soroban_sdk::{
+    testutils::{ Address as _, Events as _, Ledger },
+    token::{ StellarAssetClient, TokenClient },
+    vec,
+    Address,
+    Env,
+    String,
+    Symbol,
+    TryFromVal,
+    Vec,
+};
+
+pub use lending_pool::{ LendingPool, LendingPoolClient, Tranche };
+pub use loan_manager::{
+    ExcessPayment,
+    Loan,
+    LoanManager,
+    LoanManagerClient,
+    LoanStatus,
+    PaymentAllocation,
+    PaymentFrequency,
+};
+pub use oracle_verifier::{ OracleVerifier, OracleVerifierClient };
+pub use remittance_nft::{ PaymentRecord, RemittanceNFT, RemittanceNFTClient };
+
+pub const SECONDS_PER_MONTH: u64 = 30 * 24 * 60 * 60;
+// USDC uses 7 decimals on Stellar
+pub const USDC: i128 = 10_000_000;
+
+pub struct Protocol<'a> {
+    pub env: Env,
+    pub admin: Address,
+    pub operator: Address,
+    pub usdc: TokenClient<'a>,
+    pub usdc_admin: StellarAssetClient<'a>,
+    pub nft: RemittanceNFTClient<'a>,
+    pub pool: LendingPoolClient<'a>,
+    pub loan_manager: LoanManagerClient<'a>,
+    pub oracle: OracleVerifierClient<'a>,
+}
+
+impl<'a> Protocol<'a> {
+    // Deploy all four contracts wired to each other. Every `require_auth` is mocked, so
+    // helpers act on behalf of whichever account they are given.
+    pub fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let operator = Address::generate(&env);
+
+        let usdc_contract = env.register_stellar_asset_contract_v2(admin.clone());
+        let usdc_address = usdc_contract.address();
+
+        // Constructors need each other's addresses, so pick them before registering
+        let nft_address = Address::generate(&env);
+        let pool_address = Address::generate(&env);
+        let loan_manager_address = Address::generate(&env);
+        let oracle_address = Address::generate(&env);
+
+        env.register_at(
+            &nft_address,
+            RemittanceNFT,
+            (&admin, &oracle_address, &loan_manager_address)
+        );
+        env.register_at(
+            &pool_address,
+            LendingPool,
+            (&admin, &loan_manager_address, &usdc_address, 500u32)
+        );
+        env.register_at(
+            &loan_manager_address,
+            LoanManager,
+            (&admin, &nft_address, &pool_address, &oracle_address, &usdc_address)
+        );
+        env.register_at(
+            &oracle_address,
+            OracleVerifier,
+            (&admin, &nft_address, &loan_manager_address, vec![&env, operator.clone()])
+        );
+
+        Protocol {
+            usdc: TokenClient::new(&env, &usdc_address),
+            usdc_admin: StellarAssetClient::new(&env, &usdc_address),
+            nft: RemittanceNFTClient::new(&env, &nft_address),
+            pool: LendingPoolClient::new(&env, &pool_address),
+            loan_manager: LoanManagerClient::new(&env, &loan_manager_address),
+            oracle: OracleVerifierClient::new(&env, &oracle_address),
+            env,
+            admin,
+            operator,
+        }
+    }
+
+    // A fresh account holding `balance` USDC
+    pub fn account(&self, balance: i128) -> Address {
+        let account = Address::generate(&self.env);
+        if balance > 0 {
+            self.usdc_admin.mint(&account, &balance);
+        }
+        account
+    }
+
+    pub fn balance(&self, account: &Address) -> i128 {
+        self.usdc.balance(account)
+    }
+
+    pub fn advance_months(&self, months: u64) {
+        self.env.ledger().with_mut(|ledger| {
+            ledger.timestamp += months * SECONDS_PER_MONTH;
+        });
+    }
+
+    // Move the ledger forward to `timestamp`; never backwards
+    pub fn advance_to(&self, timestamp: u64) {
+        self.env.ledger().with_mut(|ledger| {
+            if timestamp > ledger.timestamp {
+                ledger.timestamp = timestamp;
+            }
+        });
+    }
+
+    // Move past the loan's next due date so a miss can be reported
+    pub fn advance_past_due(&self, loan_id: u64) {
+        self.advance_to(self.loan(loan_id).next_payment_due + 1);
+    }
+
+    pub fn deposit(&self, lender: &Address, amount: i128, tranche: Tranche) {
+        self.pool.deposit(lender, &amount, &tranche, &0u32);
+    }
+
+    pub fn withdraw(&self, lender: &Address, amount: i128, tranche: Tranche) {
+        self.pool.withdraw(lender, &amount, &tranche);
+    }
+
+    // Run the oracle verification flow for `user` with `months` of history, all paid at
+    // `monthly_amount`, and return the minted NFT
+    pub fn verify(&self, user: &Address, monthly_amount: i128, months: u32) -> u64 {
+        let mut history: Vec<PaymentRecord> = Vec::new(&self.env);
+        for month_index in 1..=months {
+            history.push_back(PaymentRecord { month_index, paid: true, amount: monthly_amount });
+        }
+        self.verify_with_history(user, monthly_amount, history)
+    }
+
+    pub fn verify_with_history(
+        &self,
+        user: &Address,
+        monthly_amount: i128,
+        history: Vec<PaymentRecord>
+    ) -> u64 {
+        self.oracle.request_verification(
+            user,
+            &String::from_str(&self.env, "wise"),
+            &user.to_string()
+        );
+
+        let months = history.len();
+        let total_sent = monthly_amount * (months as i128);
+        self.oracle.submit_verification(
+            &self.operator,
+            user,
+            &monthly_amount,
+            &months,
+            &total_sent,
+            &history
+        );
+
+        let tokens = self.nft.get_tokens_of(user);
+        tokens.get(tokens.len() - 1).expect("No NFT minted")
+    }
+
+    // Request a loan repaid monthly
+    pub fn request(&self, borrower: &Address, nft_id: u64, amount: i128, months: u32) -> u64 {
+        self.request_with_frequency(borrower, nft_id, amount, months, PaymentFrequency::Monthly)
+    }
+
+    pub fn request_with_frequency(
+        &self,
+        borrower: &Address,
+        nft_id: u64,
+        amount: i128,
+        months: u32,
+        frequency: PaymentFrequency
+    ) -> u64 {
+        self.loan_manager.request_loan(
+            borrower,
+            &nft_id,
+            &amount,
+            &months,
+            &self.usdc.address,
+            &frequency
+        )
+    }
+
+    pub fn approve(&self, loan_id: u64) {
+        self.loan_manager.approve_loan(&loan_id);
+    }
+
+    pub fn loan(&self, loan_id: u64) -> Loan {
+        self.loan_manager.get_loan(&loan_id)
+    }
+
+    // Pay `amount`, with any excess covering later installments
+    pub fn pay(&self, loan_id: u64, amount: i128) -> PaymentAllocation {
+        self.loan_manager.make_payment(&loan_id, &amount, &ExcessPayment::PayAhead)
+    }
+
+    // Pay the next installment, capped at what is still owed so the last one clears the loan,
+    // and move to the next due date
+    pub fn pay_installment(&self, loan_id: u64) -> i128 {
+        let amount = self.loan_manager.get_installment_due(&loan_id);
+        self.pay(loan_id, amount);
+        self.advance_to(self.loan(loan_id).next_payment_due - 1);
+        amount
+    }
+
+    // Pay installments until the loan is repaid; returns the total paid
+    pub fn pay_off(&self, loan_id: u64) -> i128 {
+        let mut total = 0;
+        while self.loan(loan_id).status == LoanStatus::Active {
+            total += self.pay_installment(loan_id);
+        }
+        total
+    }
+
+    // Let the next installment fall overdue, then have the oracle report it missed
+    pub fn miss(&self, loan_id: u64) {
+        self.advance_past_due(loan_id);
+        let nft_id = self.loan(loan_id).nft_collateral_id;
+        self.oracle.report_missed_payment(&self.operator, &loan_id, &nft_id);
+    }
+
+    // Miss payments until the loan defaults
+    pub fn default_loan(&self, loan_id: u64) {
+        while self.loan(loan_id).status == LoanStatus::Active {
+            self.miss(loan_id);
+        }
+    }
+
+    pub fn assert_balance(&self, account: &Address, expected: i128) {
+        assert_eq!(self.balance(account), expected, "unexpected USDC balance");
+    }
+
+    // Number of events `contract` published during the last top-level call whose first
+    // topic is `name`
+    pub fn event_count(&self, contract: &Address, name: &str) -> usize {
+        let topic = Symbol::new(&self.env, name);
+        self.env
+            .events()
+            .all()
+            .iter()
+            .filter(|(address, topics, _)| {
+                address == contract &&
+                    topics
+                        .first()
+                        .and_then(|first| Symbol::try_from_val(&self.env, &first).ok()) == Some(topic.clone())
+            })
+            .count()
+    }
+
+    pub fn assert_event(&self, contract: &Address, name: &str) {
+        assert!(self.event_count(contract, name) > 0, "expected a `{}` event", name);
+    }
+}
+
+impl Default for Protocol<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,182 @@
+// Random sequences of pool and loan operations against the real contracts, checking the
+// accounting invariants after every step. Operations the contracts reject are skipped.
+
+use proptest::prelude::*;
+use soroban_sdk::Address;
+use test_support::{ ExcessPayment, LoanStatus, PaymentFrequency, Protocol, Tranche, USDC };
+
+const LENDERS: usize = 3;
+const BORROWERS: usize = 3;
+const LENDER_FUNDS: i128 = 20_000 * USDC;
+const BORROWER_FUNDS: i128 = 5_000 * USDC;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Deposit { lender: usize, amount: i128, senior: bool },
+    Withdraw { lender: usize, amount: i128, senior: bool },
+    Borrow { borrower: usize, amount: i128, months: u32 },
+    Repay { loan: usize, amount: i128, prepay: bool },
+    Miss { loan: usize },
+    Advance,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..LENDERS, 1..=5_000i128, any::<bool>()).prop_map(|(lender, amount, senior)| {
+            Op::Deposit { lender, amount: amount * USDC, senior }
+        }),
+        2 => (0..LENDERS, 1..=5_000i128, any::<bool>()).prop_map(|(lender, amount, senior)| {
+            Op::Withdraw { lender, amount: amount * USDC, senior }
+        }),
+        2 => (0..BORROWERS, 1..=1_500i128, 1..=12u32).prop_map(|(borrower, amount, months)| {
+            Op::Borrow { borrower, amount: amount * USDC, months }
+        }),
+        4 => (any::<usize>(), 1..=600i128, any::<bool>()).prop_map(|(loan, amount, prepay)| {
+            Op::Repay { loan, amount: amount * USDC, prepay }
+        }),
+        1 => any::<usize>().prop_map(|loan| Op::Miss { loan }),
+        1 => Just(Op::Advance),
+    ]
+}
+
+struct World<'a> {
+    protocol: Protocol<'a>,
+    lenders: Vec<Address>,
+    deposited: Vec<i128>,
+    borrowers: Vec<(Address, u64)>,
+    loans: Vec<u64>,
+}
+
+impl World<'_> {
+    fn new() -> Self {
+        let protocol = Protocol::new();
+        let lenders = (0..LENDERS).map(|_| protocol.account(LENDER_FUNDS)).collect();
+        let borrowers = (0..BORROWERS)
+            .map(|_| {
+                let borrower = protocol.account(BORROWER_FUNDS);
+                let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+                (borrower, nft_id)
+            })
+            .collect();
+
+        World { protocol, lenders, deposited: vec![0; LENDERS], borrowers, loans: Vec::new() }
+    }
+
+    fn apply(&mut self, op: &Op) {
+        let protocol = &self.protocol;
+        match *op {
+            Op::Deposit { lender, amount, senior } => {
+                let result = protocol.pool.try_deposit(&self.lenders[lender], &amount, &tranche(senior), &0u32);
+                if result.is_ok() {
+                    self.deposited[lender] += amount;
+                }
+            }
+            Op::Withdraw { lender, amount, senior } => {
+                let _ = protocol.pool.try_withdraw(&self.lenders[lender], &amount, &tranche(senior));
+            }
+            Op::Borrow { borrower, amount, months } => {
+                let (address, nft_id) = &self.borrowers[borrower];
+                let requested = protocol.loan_manager.try_request_loan(
+                    address,
+                    nft_id,
+                    &amount,
+                    &months,
+                    &protocol.usdc.address,
+                    &PaymentFrequency::Monthly
+                );
+                if let Ok(Ok(loan_id)) = requested {
+                    self.loans.push(loan_id);
+                    let _ = protocol.loan_manager.try_approve_loan(&loan_id);
+                }
+            }
+            Op::Repay { loan, amount, prepay } => {
+                if let Some(loan_id) = self.pick_active(loan) {
+                    let excess = if prepay {
+                        ExcessPayment::ReducePrincipal
+                    } else {
+                        ExcessPayment::PayAhead
+                    };
+                    let _ = protocol.loan_manager.try_make_payment(&loan_id, &amount, &excess);
+                }
+            }
+            Op::Miss { loan } => {
+                if let Some(loan_id) = self.pick_active(loan) {
+                    protocol.advance_past_due(loan_id);
+                    let nft_id = protocol.loan(loan_id).nft_collateral_id;
+                    let _ = protocol.oracle.try_report_missed_payment(&protocol.operator, &loan_id, &nft_id);
+                }
+            }
+            Op::Advance => protocol.advance_months(1),
+        }
+    }
+
+    fn pick_active(&self, index: usize) -> Option<u64> {
+        if self.loans.is_empty() {
+            return None;
+        }
+        let loan_id = self.loans[index % self.loans.len()];
+        if self.protocol.loan(loan_id).status == LoanStatus::Active { Some(loan_id) } else { None }
+    }
+
+    fn check_invariants(&self) {
+        let protocol = &self.protocol;
+        let pool = &protocol.pool;
+
+        // The pool's USDC covers idle principal, interest owed to lenders, the treasury and
+        // the insurance reserve
+        let mut pending_interest = 0;
+        for lender in &self.lenders {
+            for senior in [true, false] {
+                pending_interest += pool.get_lender_info(lender, &tranche(senior)).pending_interest;
+            }
+        }
+        let liabilities =
+            pool.get_available_liquidity() +
+            pending_interest +
+            pool.get_treasury_balance() +
+            pool.get_insurance_reserve();
+        let held = protocol.balance(&pool.address);
+        assert!(held >= liabilities, "pool holds {} but owes {}", held, liabilities);
+        assert!(pool.get_available_liquidity() >= 0);
+
+        // No lender gets back more than they put in plus the interest they earned
+        for (i, lender) in self.lenders.iter().enumerate() {
+            let received = protocol.balance(lender) - (LENDER_FUNDS - self.deposited[i]);
+            let mut earned = 0;
+            for senior in [true, false] {
+                earned += pool.get_lender_info(lender, &tranche(senior)).earned_interest;
+            }
+            assert!(
+                received <= self.deposited[i] + earned,
+                "lender {} received {} from {} deposited and {} earned",
+                i,
+                received,
+                self.deposited[i],
+                earned
+            );
+        }
+
+        for loan_id in &self.loans {
+            let loan = protocol.loan(*loan_id);
+            assert!(loan.outstanding_balance >= 0, "loan {} balance went negative", loan_id);
+            assert!(loan.outstanding_balance <= loan.loan_amount);
+        }
+    }
+}
+
+fn tranche(senior: bool) -> Tranche {
+    if senior { Tranche::Senior } else { Tranche::Junior }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn accounting_invariants_hold(ops in prop::collection::vec(op(), 1..40)) {
+        let mut world = World::new();
+        for op in &ops {
+            world.apply(op);
+            world.check_invariants();
+        }
+    }
+}
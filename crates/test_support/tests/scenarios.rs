@@ -0,0 +1,304 @@
+use test_support::{ ExcessPayment, LoanStatus, PaymentFrequency, Protocol, Tranche, USDC };
+
+#[test]
+fn repaid_loan_pays_lender_interest() {
+    let protocol = Protocol::new();
+
+    let lender = protocol.account(10_000 * USDC);
+    protocol.deposit(&lender, 10_000 * USDC, Tranche::Junior);
+    protocol.assert_balance(&protocol.pool.address, 10_000 * USDC);
+
+    let borrower = protocol.account(1_000 * USDC);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    assert_eq!(protocol.nft.get_nft_data(&nft_id).reliability_score, 100);
+
+    let loan_id = protocol.request(&borrower, nft_id, 1_000 * USDC, 6);
+    protocol.approve(loan_id);
+    protocol.assert_event(&protocol.loan_manager.address, "loan_approved");
+    protocol.assert_balance(&borrower, 2_000 * USDC);
+    assert!(protocol.nft.get_nft_data(&nft_id).is_staked);
+
+    let paid = protocol.pay_off(loan_id);
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.status, LoanStatus::Repaid);
+    assert_eq!(loan.outstanding_balance, 0);
+    assert!(paid > 1_000 * USDC);
+    assert!(!protocol.nft.get_nft_data(&nft_id).is_staked);
+
+    // The sole lender earns all the interest, less per-share rounding
+    let info = protocol.pool.get_lender_info(&lender, &Tranche::Junior);
+    let interest = paid - 1_000 * USDC;
+    assert!(info.pending_interest <= interest);
+    assert!(info.pending_interest >= interest - 1_000);
+
+    protocol.withdraw(&lender, 10_000 * USDC, Tranche::Junior);
+    protocol.assert_event(&protocol.pool.address, "withdraw");
+    protocol.assert_balance(&lender, 10_000 * USDC + info.pending_interest);
+}
+
+#[test]
+fn default_is_absorbed_by_junior_before_senior() {
+    let protocol = Protocol::new();
+
+    let senior = protocol.account(5_000 * USDC);
+    let junior = protocol.account(1_000 * USDC);
+    protocol.deposit(&senior, 5_000 * USDC, Tranche::Senior);
+    protocol.deposit(&junior, 1_000 * USDC, Tranche::Junior);
+
+    let borrower = protocol.account(0);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    let loan_id = protocol.request(&borrower, nft_id, 800 * USDC, 6);
+    protocol.approve(loan_id);
+
+    protocol.miss(loan_id);
+    assert_eq!(protocol.loan(loan_id).status, LoanStatus::Active);
+    protocol.miss(loan_id);
+    protocol.assert_event(&protocol.pool.address, "write_off");
+    assert_eq!(protocol.loan(loan_id).status, LoanStatus::Defaulted);
+    assert_eq!(protocol.pool.get_reserve_draws().len(), 1);
+
+    let junior_info = protocol.pool.get_lender_info(&junior, &Tranche::Junior);
+    let senior_info = protocol.pool.get_lender_info(&senior, &Tranche::Senior);
+    assert_eq!(junior_info.deposit_amount, 200 * USDC);
+    assert_eq!(senior_info.deposit_amount, 5_000 * USDC);
+
+    // The missed payments also count against the borrower's score
+    assert!(protocol.nft.get_nft_data(&nft_id).reliability_score < 100);
+}
+
+#[test]
+fn withdrawal_waits_in_queue_until_repayment() {
+    let protocol = Protocol::new();
+
+    let lender = protocol.account(1_000 * USDC);
+    protocol.deposit(&lender, 1_000 * USDC, Tranche::Junior);
+
+    let borrower = protocol.account(1_000 * USDC);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    let loan_id = protocol.request(&borrower, nft_id, 900 * USDC, 3);
+    protocol.approve(loan_id);
+
+    // Only 100 USDC is idle, so the request is queued and filled as far as liquidity allows
+    protocol.withdraw(&lender, 500 * USDC, Tranche::Junior);
+    protocol.assert_event(&protocol.pool.address, "withdrawal_queued");
+    protocol.assert_balance(&lender, 0);
+
+    protocol.pool.process_withdrawals();
+    protocol.assert_balance(&lender, 100 * USDC);
+    assert_eq!(protocol.pool.get_queued_withdrawals(), 400 * USDC);
+
+    protocol.pay_off(loan_id);
+    assert_eq!(protocol.pool.get_queued_withdrawals(), 0);
+    assert!(protocol.balance(&lender) >= 500 * USDC);
+}
+
+#[test]
+fn restructured_loan_recovers_instead_of_defaulting() {
+    let protocol = Protocol::new();
+
+    let lender = protocol.account(5_000 * USDC);
+    protocol.deposit(&lender, 5_000 * USDC, Tranche::Junior);
+
+    let borrower = protocol.account(2_000 * USDC);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    let loan_id = protocol.request(&borrower, nft_id, 900 * USDC, 3);
+    protocol.approve(loan_id);
+
+    protocol.miss(loan_id);
+    let before = protocol.loan(loan_id);
+    protocol.loan_manager.restructure_loan(&protocol.admin, &loan_id, &12u32, &1000u32);
+    protocol.assert_event(&protocol.loan_manager.address, "loan_restructured");
+
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.payments_missed, 0);
+    assert_eq!(loan.duration_months, 12);
+    assert!(loan.installment_amount < before.installment_amount);
+    assert_eq!(loan.restructurings.len(), 1);
+    let entry = loan.restructurings.get(0).unwrap();
+    assert_eq!(entry.previous_rate, before.interest_rate);
+    assert_eq!(entry.payments_missed, 1);
+
+    // A second miss after restructuring no longer defaults the loan
+    protocol.miss(loan_id);
+    assert_eq!(protocol.loan(loan_id).status, LoanStatus::Active);
+
+    protocol.pay_off(loan_id);
+    assert_eq!(protocol.loan(loan_id).status, LoanStatus::Repaid);
+
+    // Borrowers cannot restructure their own loans
+    let other = protocol.request(&borrower, nft_id, 500 * USDC, 6);
+    protocol.approve(other);
+    assert!(protocol.loan_manager.try_restructure_loan(&borrower, &other, &12u32, &0u32).is_err());
+}
+
+#[test]
+fn deferred_cycle_is_not_a_miss_and_capitalizes_interest() {
+    let protocol = Protocol::new();
+
+    let lender = protocol.account(5_000 * USDC);
+    protocol.deposit(&lender, 5_000 * USDC, Tranche::Junior);
+
+    let borrower = protocol.account(2_000 * USDC);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    let loan_id = protocol.request(&borrower, nft_id, 1_000 * USDC, 6);
+    protocol.approve(loan_id);
+    let before = protocol.loan(loan_id);
+
+    // A perfect score clears the default auto-approval threshold
+    assert!(protocol.loan_manager.request_deferral(&loan_id));
+    protocol.assert_event(&protocol.loan_manager.address, "payment_deferred");
+
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.deferrals_used, 1);
+    assert!(loan.capitalized_interest > 0);
+    assert_eq!(loan.outstanding_balance, before.outstanding_balance + loan.capitalized_interest);
+    assert_eq!(loan.next_payment_due, before.next_payment_due + test_support::SECONDS_PER_MONTH);
+
+    // The oracle cannot report the skipped cycle as a miss
+    let report = protocol.oracle.try_report_missed_payment(&protocol.operator, &loan_id, &nft_id);
+    assert!(report.is_err());
+    assert_eq!(protocol.nft.get_nft_data(&nft_id).reliability_score, 100);
+
+    // The capitalized interest is repaid to lenders as interest
+    let paid = protocol.pay_off(loan_id);
+    assert_eq!(protocol.loan(loan_id).capitalized_interest, 0);
+    let info = protocol.pool.get_lender_info(&lender, &Tranche::Junior);
+    assert!(info.pending_interest >= paid - 1_000 * USDC - 1_000);
+}
+
+#[test]
+fn weekly_remittance_pays_every_overdue_cycle() {
+    let protocol = Protocol::new();
+
+    let lender = protocol.account(5_000 * USDC);
+    protocol.deposit(&lender, 5_000 * USDC, Tranche::Junior);
+
+    let borrower = protocol.account(1_000 * USDC);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    let loan_id = protocol.request_with_frequency(
+        &borrower,
+        nft_id,
+        520 * USDC,
+        6,
+        PaymentFrequency::Weekly
+    );
+    protocol.approve(loan_id);
+    protocol.oracle.start_monitoring_loan(&loan_id);
+
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.installments, 26);
+    let week = 7 * 24 * 60 * 60;
+    assert_eq!(loan.next_payment_due, loan.start_timestamp + week);
+
+    // A remittance sent two weeks after the first due date covers three installments
+    protocol.advance_to(loan.start_timestamp + 3 * week);
+    let sent_at = protocol.env.ledger().timestamp();
+    assert_eq!(protocol.loan_manager.get_cycles_due(&loan_id, &sent_at), 3);
+    protocol.oracle.report_remittance(
+        &protocol.operator,
+        &borrower,
+        &nft_id,
+        &(500 * USDC),
+        &loan_id,
+        &sent_at
+    );
+
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.payments_made, 3);
+    assert_eq!(loan.next_payment_due, loan.start_timestamp + 4 * week);
+}
+
+#[test]
+fn payments_follow_the_allocation_waterfall() {
+    let protocol = Protocol::new();
+    protocol.loan_manager.set_late_fee(&500u32);
+
+    let lender = protocol.account(5_000 * USDC);
+    protocol.deposit(&lender, 5_000 * USDC, Tranche::Junior);
+
+    let borrower = protocol.account(1_000 * USDC);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    let loan_id = protocol.request(&borrower, nft_id, 1_200 * USDC, 12);
+    protocol.approve(loan_id);
+    let start = protocol.loan(loan_id);
+    let month = test_support::SECONDS_PER_MONTH;
+
+    // A partial payment goes to interest and leaves the cycle open
+    let partial = protocol.pay(loan_id, 10 * USDC);
+    assert_eq!(partial.interest, 10 * USDC);
+    assert_eq!(partial.cycles_satisfied, 0);
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.payments_made, 0);
+    assert_eq!(loan.next_payment_due, start.next_payment_due);
+    assert_eq!(loan.installment_paid, 10 * USDC);
+
+    // The rest of the installment closes it
+    let rest = protocol.loan_manager.get_installment_due(&loan_id);
+    assert_eq!(rest, start.installment_amount - 10 * USDC);
+    assert_eq!(protocol.pay(loan_id, rest).cycles_satisfied, 1);
+
+    // Three installments at once pay three cycles ahead
+    let ahead = protocol.pay(loan_id, 3 * start.installment_amount);
+    assert_eq!(ahead.cycles_satisfied, 3);
+    assert_eq!(ahead.principal_prepaid, 0);
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.payments_made, 4);
+    assert_eq!(loan.next_payment_due, start.next_payment_due + 4 * month);
+
+    // Or the excess can go straight to principal, keeping the due dates
+    let balance = loan.outstanding_balance;
+    let prepay = protocol.loan_manager.make_payment(
+        &loan_id,
+        &(start.installment_amount + 100 * USDC),
+        &ExcessPayment::ReducePrincipal
+    );
+    assert_eq!(prepay.cycles_satisfied, 1);
+    assert_eq!(prepay.principal_prepaid, 100 * USDC);
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.outstanding_balance, balance - prepay.principal_due - 100 * USDC);
+    assert_eq!(loan.next_payment_due, start.next_payment_due + 5 * month);
+
+    // A missed installment adds a late fee, which is paid before anything else
+    protocol.miss(loan_id);
+    let fee = (start.installment_amount * 500) / 10000;
+    assert_eq!(protocol.loan(loan_id).fees_due, fee);
+    let next = protocol.pay(loan_id, fee + 1);
+    assert_eq!(next.fees, fee);
+    assert_eq!(next.interest, 1);
+
+    protocol.pay_off(loan_id);
+    assert_eq!(protocol.loan(loan_id).status, LoanStatus::Repaid);
+    assert_eq!(protocol.loan_manager.get_payoff_amount(&loan_id), 0);
+}
+
+#[test]
+fn partial_payment_on_final_installment_leaves_the_rest_due() {
+    let protocol = Protocol::new();
+
+    let lender = protocol.account(5_000 * USDC);
+    protocol.deposit(&lender, 5_000 * USDC, Tranche::Junior);
+
+    let borrower = protocol.account(1_000 * USDC);
+    let nft_id = protocol.verify(&borrower, 1_000 * USDC, 12);
+    let loan_id = protocol.request(&borrower, nft_id, 600 * USDC, 3);
+    protocol.approve(loan_id);
+
+    protocol.pay_installment(loan_id);
+    protocol.pay_installment(loan_id);
+    assert_eq!(protocol.loan(loan_id).payments_made, 2);
+
+    // Half of the final installment leaves the other half due, not less
+    let last = protocol.loan_manager.get_installment_due(&loan_id);
+    assert_eq!(last, protocol.loan_manager.get_payoff_amount(&loan_id));
+    let partial = protocol.pay(loan_id, last / 2);
+    assert_eq!(partial.cycles_satisfied, 0);
+
+    let rest = protocol.loan_manager.get_installment_due(&loan_id);
+    assert_eq!(rest, last - last / 2);
+    assert_eq!(rest, protocol.loan_manager.get_payoff_amount(&loan_id));
+
+    assert_eq!(protocol.pay(loan_id, rest).cycles_satisfied, 1);
+    let loan = protocol.loan(loan_id);
+    assert_eq!(loan.status, LoanStatus::Repaid);
+    assert_eq!(loan.outstanding_balance, 0);
+}
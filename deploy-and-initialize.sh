@@ -1,7 +1,7 @@
 #!/bin/bash
 
-# Full deployment and initialization script for RemitLend contracts
-# This script deploys all contracts with proper initialization
+# Full deployment script for RemitLend contracts
+# This script deploys all contracts with their constructor config
 
 set -e  # Exit on error
 
@@ -14,6 +14,7 @@ cargo build --release --target wasm32-unknown-unknown \
   -p remittance_nft \
   -p lending_pool \
   -p loan_manager \
+  -p oracle_verifier \
   -p rmtlend
 
 echo "✅ Contracts built successfully!"
@@ -37,92 +38,116 @@ echo ""
 # Placeholder USDC token address (Stellar testnet USDC)
 USDC_TOKEN="CBIELTK6YBZJU5UP2WWQEUCYKLPU6AUNZ2BQ4WWFEIE3USCIHMXQDAMA"
 
-# Step 1: Deploy RemittanceNFT (needs oracle and loan_manager, but we'll set them after)
+# Every contract takes its config in its constructor, so the addresses of the others must be
+# known before anything is deployed. Each contract is deployed with a fixed salt, which lets
+# us compute all five addresses up front. If an address needs to change later, use the admin
+# setters (set_oracle, set_loan_manager, set_nft_contract, ...) and check it with get_config.
+SALT_PREFIX=$(openssl rand -hex 28)
+
+predict_id() {
+  stellar contract id wasm \
+    --salt "$1" \
+    --source default \
+    --network testnet
+}
+
+NFT_SALT="${SALT_PREFIX}00000001"
+POOL_SALT="${SALT_PREFIX}00000002"
+LOAN_MANAGER_SALT="${SALT_PREFIX}00000003"
+ORACLE_SALT="${SALT_PREFIX}00000004"
+RMTLEND_SALT="${SALT_PREFIX}00000005"
+
+REMITTANCE_NFT_ID=$(predict_id $NFT_SALT)
+LENDING_POOL_ID=$(predict_id $POOL_SALT)
+LOAN_MANAGER_ID=$(predict_id $LOAN_MANAGER_SALT)
+ORACLE_VERIFIER_ID=$(predict_id $ORACLE_SALT)
+RMTLEND_ID=$(predict_id $RMTLEND_SALT)
+
+# Step 1: Deploy RemittanceNFT
 echo "1️⃣  Deploying RemittanceNFT contract..."
-REMITTANCE_NFT_ID=$(stellar contract deploy \
+stellar contract deploy \
   --wasm ./target/wasm32-unknown-unknown/release/remittance_nft.wasm \
+  --salt $NFT_SALT \
   --source default \
-  --network testnet)
+  --network testnet \
+  -- \
+  --admin $DEPLOYER_KEY \
+  --oracle $ORACLE_VERIFIER_ID \
+  --loan_manager $LOAN_MANAGER_ID
 
 echo "   ✅ RemittanceNFT deployed: $REMITTANCE_NFT_ID"
 echo ""
 
-# Step 2: Deploy LendingPool (needs loan_manager and usdc_token)
+# Step 2: Deploy LendingPool
 echo "2️⃣  Deploying LendingPool contract..."
-LENDING_POOL_ID=$(stellar contract deploy \
+stellar contract deploy \
   --wasm ./target/wasm32-unknown-unknown/release/lending_pool.wasm \
+  --salt $POOL_SALT \
   --source default \
-  --network testnet)
+  --network testnet \
+  -- \
+  --admin $DEPLOYER_KEY \
+  --loan_manager $LOAN_MANAGER_ID \
+  --usdc_token $USDC_TOKEN \
+  --base_rate 500
 
 echo "   ✅ LendingPool deployed: $LENDING_POOL_ID"
 echo ""
 
-# Step 3: Deploy LoanManager (needs nft, pool, oracle, usdc)
+# Step 3: Deploy LoanManager
 echo "3️⃣  Deploying LoanManager contract..."
-LOAN_MANAGER_ID=$(stellar contract deploy \
+stellar contract deploy \
   --wasm ./target/wasm32-unknown-unknown/release/loan_manager.wasm \
+  --salt $LOAN_MANAGER_SALT \
   --source default \
-  --network testnet)
+  --network testnet \
+  -- \
+  --admin $DEPLOYER_KEY \
+  --nft_contract $REMITTANCE_NFT_ID \
+  --pool_contract $LENDING_POOL_ID \
+  --oracle_contract $ORACLE_VERIFIER_ID \
+  --usdc_token $USDC_TOKEN
 
 echo "   ✅ LoanManager deployed: $LOAN_MANAGER_ID"
 echo ""
 
-# Step 4: Deploy RmtLend (needs loan_manager and pool)
-echo "4️⃣  Deploying RmtLend contract..."
-RMTLEND_ID=$(stellar contract deploy \
-  --wasm ./target/wasm32-unknown-unknown/release/rmtlend.wasm \
-  --source default \
-  --network testnet)
-
-echo "   ✅ RmtLend deployed: $RMTLEND_ID"
-echo ""
-
-# Now initialize the contracts
-echo "🔧 Initializing contracts..."
-echo ""
-
-# Initialize RemittanceNFT
-echo "Initializing RemittanceNFT..."
-stellar contract invoke \
-  --id $REMITTANCE_NFT_ID \
-  --source default \
-  --network testnet \
-  -- initialize \
-  --oracle $REMITTANCE_NFT_ID \
-  --loan_manager $LOAN_MANAGER_ID || echo "⚠️  RemittanceNFT initialization skipped (might use __initialize)"
-
-# Initialize LendingPool
-echo "Initializing LendingPool..."
-stellar contract invoke \
-  --id $LENDING_POOL_ID \
+# Step 4: Deploy OracleVerifier
+echo "4️⃣  Deploying OracleVerifier contract..."
+stellar contract deploy \
+  --wasm ./target/wasm32-unknown-unknown/release/oracle_verifier.wasm \
+  --salt $ORACLE_SALT \
   --source default \
   --network testnet \
-  -- initialize \
+  -- \
+  --admin $DEPLOYER_KEY \
+  --nft_contract $REMITTANCE_NFT_ID \
   --loan_manager $LOAN_MANAGER_ID \
-  --usdc_token $USDC_TOKEN \
-  --base_rate 500 \
-  --utilization_multiplier 200 || echo "⚠️  LendingPool initialization skipped (might use __initialize)"
+  --operators "[\"$DEPLOYER_KEY\"]"
 
-# Initialize LoanManager
-echo "Initializing LoanManager..."
-stellar contract invoke \
-  --id $LOAN_MANAGER_ID \
+echo "   ✅ OracleVerifier deployed: $ORACLE_VERIFIER_ID"
+echo ""
+
+# Step 5: Deploy RmtLend (router in front of the four contracts above)
+echo "5️⃣  Deploying RmtLend contract..."
+stellar contract deploy \
+  --wasm ./target/wasm32-unknown-unknown/release/rmtlend.wasm \
+  --salt $RMTLEND_SALT \
   --source default \
   --network testnet \
-  -- initialize \
-  --nft_contract $REMITTANCE_NFT_ID \
-  --pool_contract $LENDING_POOL_ID \
-  --oracle_contract $REMITTANCE_NFT_ID \
-  --usdc_token $USDC_TOKEN || echo "⚠️  LoanManager initialization skipped (might use __initialize)"
+  -- \
+  --admin $DEPLOYER_KEY \
+  --registry "{\"remittance_nft\":\"$REMITTANCE_NFT_ID\",\"lending_pool\":\"$LENDING_POOL_ID\",\"loan_manager\":\"$LOAN_MANAGER_ID\",\"oracle_verifier\":\"$ORACLE_VERIFIER_ID\"}"
 
+echo "   ✅ RmtLend deployed: $RMTLEND_ID"
 echo ""
-echo "✅ All contracts deployed and initialized!"
+echo "✅ All contracts deployed and configured!"
 echo ""
 echo "📋 Contract Addresses:"
 echo "================================"
 echo "RemittanceNFT:  $REMITTANCE_NFT_ID"
 echo "LendingPool:    $LENDING_POOL_ID"
 echo "LoanManager:    $LOAN_MANAGER_ID"
+echo "OracleVerifier: $ORACLE_VERIFIER_ID"
 echo "RmtLend:        $RMTLEND_ID"
 echo "================================"
 echo ""
@@ -147,6 +172,7 @@ PUBLIC_STELLAR_HORIZON_URL="https://horizon-testnet.stellar.org"
 PUBLIC_REMITTANCE_NFT_CONTRACT_ID="$REMITTANCE_NFT_ID"
 PUBLIC_LENDING_POOL_CONTRACT_ID="$LENDING_POOL_ID"
 PUBLIC_LOAN_MANAGER_CONTRACT_ID="$LOAN_MANAGER_ID"
+PUBLIC_ORACLE_VERIFIER_CONTRACT_ID="$ORACLE_VERIFIER_ID"
 PUBLIC_RMTLEND_CONTRACT_ID="$RMTLEND_ID"
 EOF
 
@@ -156,6 +182,3 @@ echo "✅ .env file updated!"
 echo ""
 echo "🎉 Deployment complete! You can now use the dApp."
 echo ""
-echo "⚠️  Note: If initialization failed above, the contracts use __initialize"
-echo "   which is called automatically on first deployment. The contracts should"
-echo "   still work, but may need manual initialization via a separate invoke."
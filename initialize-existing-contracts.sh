@@ -1,53 +1,75 @@
 #!/bin/bash
 
-# Initialize existing deployed contracts
-# This uses the new public initialize() functions
+# Re-wire existing deployed contracts
+# Contracts are configured by their constructors at deploy time, so there is nothing left to
+# initialize. This points already-deployed contracts at each other using the admin setters,
+# e.g. after one of them has been redeployed and its ID updated in .env.
 
 set -e
 
-echo "🔧 Initializing RemitLend contracts..."
+echo "🔧 Re-wiring RemitLend contracts..."
 echo ""
 
 # Load contract IDs from .env
 source .env
 
-# Your wallet address (you'll need to sign these transactions)
-echo "⚠️  You will need to sign transactions to initialize the contracts."
+# Stellar testnet USDC, the asset the LendingPool was deployed with
+USDC_TOKEN="CBIELTK6YBZJU5UP2WWQEUCYKLPU6AUNZ2BQ4WWFEIE3USCIHMXQDAMA"
+
+# Your wallet address (you'll need to sign these transactions as the admin)
+echo "⚠️  You will need to sign transactions as the contracts' admin."
+echo ""
+
+invoke() {
+  stellar contract invoke \
+    --id "$1" \
+    --source-account deployer \
+    --network testnet \
+    -- "${@:2}"
+}
+
+# RemittanceNFT
+echo "1️⃣  Wiring RemittanceNFT..."
+invoke $PUBLIC_REMITTANCE_NFT_CONTRACT_ID set_oracle --oracle $PUBLIC_ORACLE_VERIFIER_CONTRACT_ID
+invoke $PUBLIC_REMITTANCE_NFT_CONTRACT_ID set_loan_manager --loan_manager $PUBLIC_LOAN_MANAGER_CONTRACT_ID
+
+echo "   ✅ RemittanceNFT wired!"
+echo ""
+
+# LendingPool
+echo "2️⃣  Wiring LendingPool..."
+invoke $PUBLIC_LENDING_POOL_CONTRACT_ID set_loan_manager --loan_manager $PUBLIC_LOAN_MANAGER_CONTRACT_ID
+
+echo "   ✅ LendingPool wired!"
 echo ""
 
-# Initialize RemittanceNFT
-echo "1️⃣  Initializing RemittanceNFT..."
-stellar contract invoke \
-  --id $PUBLIC_REMITTANCE_NFT_CONTRACT_ID \
-  --source-account deployer \
-  --network testnet \
-  -- initialize \
-  --admin $(stellar keys address deployer) \
-  --oracle $PUBLIC_REMITTANCE_NFT_CONTRACT_ID \
-  --loan_manager $PUBLIC_LOAN_MANAGER_CONTRACT_ID
-
-echo "   ✅ RemittanceNFT initialized!"
+# LoanManager
+echo "3️⃣  Wiring LoanManager..."
+invoke $PUBLIC_LOAN_MANAGER_CONTRACT_ID set_nft_contract --nft_contract $PUBLIC_REMITTANCE_NFT_CONTRACT_ID
+invoke $PUBLIC_LOAN_MANAGER_CONTRACT_ID set_oracle_contract --oracle_contract $PUBLIC_ORACLE_VERIFIER_CONTRACT_ID
+invoke $PUBLIC_LOAN_MANAGER_CONTRACT_ID add_pool \
+  --asset $USDC_TOKEN \
+  --pool_contract $PUBLIC_LENDING_POOL_CONTRACT_ID
+
+echo "   ✅ LoanManager wired!"
 echo ""
 
-# Initialize LoanManager
-echo "2️⃣  Initializing LoanManager..."
-stellar contract invoke \
-  --id $PUBLIC_LOAN_MANAGER_CONTRACT_ID \
-  --source-account deployer \
-  --network testnet \
-  -- initialize \
-  --admin $(stellar keys address deployer) \
-  --nft_contract $PUBLIC_REMITTANCE_NFT_CONTRACT_ID \
-  --pool_contract $PUBLIC_LENDING_POOL_CONTRACT_ID \
-  --oracle_contract $PUBLIC_REMITTANCE_NFT_CONTRACT_ID \
-  --usdc_token CBIELTK6YBZJU5UP2WWQEUCYKLPU6AUNZ2BQ4WWFEIE3USCIHMXQDAMA
-
-echo "   ✅ LoanManager initialized!"
+# OracleVerifier
+echo "4️⃣  Wiring OracleVerifier..."
+invoke $PUBLIC_ORACLE_VERIFIER_CONTRACT_ID set_nft_contract --nft_contract $PUBLIC_REMITTANCE_NFT_CONTRACT_ID
+invoke $PUBLIC_ORACLE_VERIFIER_CONTRACT_ID set_loan_manager --loan_manager $PUBLIC_LOAN_MANAGER_CONTRACT_ID
+
+echo "   ✅ OracleVerifier wired!"
 echo ""
 
-echo "🎉 All contracts initialized successfully!"
+# RmtLend registry (ProtocolContract: 0 = RemittanceNft, 1 = LendingPool, 2 = LoanManager, 3 = OracleVerifier)
+echo "5️⃣  Wiring RmtLend..."
+invoke $PUBLIC_RMTLEND_CONTRACT_ID set_contract --kind 0 --address $PUBLIC_REMITTANCE_NFT_CONTRACT_ID
+invoke $PUBLIC_RMTLEND_CONTRACT_ID set_contract --kind 1 --address $PUBLIC_LENDING_POOL_CONTRACT_ID
+invoke $PUBLIC_RMTLEND_CONTRACT_ID set_contract --kind 2 --address $PUBLIC_LOAN_MANAGER_CONTRACT_ID
+invoke $PUBLIC_RMTLEND_CONTRACT_ID set_contract --kind 3 --address $PUBLIC_ORACLE_VERIFIER_CONTRACT_ID
+
+echo "   ✅ RmtLend wired!"
 echo ""
-echo "You can now use the dApp to:"
-echo "  - Mint remittance NFTs"
-echo "  - Request loans with NFT collateral"
-echo "  - Make loan payments"
+
+echo "🎉 All contracts wired successfully! Check each one with get_config."
@@ -78,6 +78,7 @@ export const CONTRACTS = {
   LENDING_POOL: "CC5B4P4JJQMCTOYHR6LD6MYKPGAEHCOMIMJ6V65KM5X5XVUA6VQZ6RQG",
   REMITTANCE_NFT: "CBYVSIBDGYZMNS5VFZ3AVOEGCYEXPPVTBAKQHQ25JM5S7CT4EZNNOU4I",
   TEST_TOKEN: "CBDCY7FEWZ6P7ETHLD7OMMVTU5UMJ4JEFYGYVO22ZYJGIL7JAC5BESPT",
+  RMTLEND: import.meta.env.PUBLIC_RMTLEND_CONTRACT_ID,
 } as const;
 
 export const toScVal = {
@@ -6,39 +6,34 @@ import {
   submitTransaction,
   toScVal,
 } from "./contractHelpers";
-import { scValToNative } from "@stellar/stellar-sdk";
+import { scValToNative, xdr } from "@stellar/stellar-sdk";
 
 type SignTransactionFn = (transactionXdr: string) => Promise<string>;
 
-type MintRemittanceNFTParams = {
+type RequestVerificationParams = {
   publicKey: string;
   signTransaction: SignTransactionFn;
-  monthlyAmount: bigint;
-  reliabilityScore: number;
-  historyMonths: number;
-  totalSent: bigint;
+  provider: string;
+  accountId: string;
 };
 
-export const mintRemittanceNFT = async ({
+// Minting is oracle-only: the borrower files a verification request and the
+// oracle mints (or refreshes) the reputation NFT once the provider confirms.
+export const requestVerification = async ({
   publicKey,
   signTransaction,
-  monthlyAmount,
-  reliabilityScore,
-  historyMonths,
-  totalSent,
-}: MintRemittanceNFTParams) => {
+  provider,
+  accountId,
+}: RequestVerificationParams) => {
   const args = [
     toScVal.address(publicKey),
-    toScVal.i128(monthlyAmount),
-    toScVal.u32(reliabilityScore),
-    toScVal.u32(historyMonths),
-    toScVal.i128(totalSent),
-    toScVal.vec([]),
+    toScVal.string(provider),
+    toScVal.string(accountId),
   ];
 
   const { transaction } = await buildContractTransaction({
-    contractId: CONTRACTS.REMITTANCE_NFT,
-    method: "mint",
+    contractId: CONTRACTS.ORACLE_VERIFIER,
+    method: "request_verification",
     args,
     publicKey,
   });
@@ -47,10 +42,21 @@ export const mintRemittanceNFT = async ({
   return submitTransaction(signedTxXdr);
 };
 
+// Mirrors LendingPool's `Tranche`
+export const Tranche = {
+  Senior: 0,
+  Junior: 1,
+} as const;
+
+export type Tranche = (typeof Tranche)[keyof typeof Tranche];
+
 type DepositToLendingPoolParams = {
   publicKey: string;
   signTransaction: SignTransactionFn;
   amount: bigint;
+  tranche?: Tranche;
+  // Must match one of the pool's lock tiers (0, 3, 6 or 12 by default)
+  lockMonths?: number;
 };
 
 const ALLOWANCE_LEDGER_BUFFER = 10_000;
@@ -166,6 +172,8 @@ export const depositToLendingPool = async ({
   publicKey,
   signTransaction,
   amount,
+  tranche = Tranche.Senior,
+  lockMonths = 0,
 }: DepositToLendingPoolParams) => {
   const currentAllowance = await fetchCurrentAllowance(publicKey);
 
@@ -175,7 +183,12 @@ export const depositToLendingPool = async ({
     );
   }
 
-  const args = [toScVal.address(publicKey), toScVal.i128(amount)];
+  const args = [
+    toScVal.address(publicKey),
+    toScVal.i128(amount),
+    toScVal.u32(tranche),
+    toScVal.u32(lockMonths),
+  ];
 
   const { transaction } = await buildContractTransaction({
     contractId: CONTRACTS.LENDING_POOL,
@@ -192,14 +205,20 @@ type WithdrawFromLendingPoolParams = {
   publicKey: string;
   signTransaction: SignTransactionFn;
   amount: bigint;
+  tranche?: Tranche;
 };
 
 export const withdrawFromLendingPool = async ({
   publicKey,
   signTransaction,
   amount,
+  tranche = Tranche.Senior,
 }: WithdrawFromLendingPoolParams) => {
-  const args = [toScVal.address(publicKey), toScVal.i128(amount)];
+  const args = [
+    toScVal.address(publicKey),
+    toScVal.i128(amount),
+    toScVal.u32(tranche),
+  ];
 
   const { transaction } = await buildContractTransaction({
     contractId: CONTRACTS.LENDING_POOL,
@@ -212,12 +231,24 @@ export const withdrawFromLendingPool = async ({
   return submitTransaction(signedTxXdr);
 };
 
+// Mirrors LoanManager's `PaymentFrequency`
+export const PaymentFrequency = {
+  Weekly: 0,
+  BiWeekly: 1,
+  Monthly: 2,
+} as const;
+
+export type PaymentFrequency =
+  (typeof PaymentFrequency)[keyof typeof PaymentFrequency];
+
 type RequestLoanParams = {
   publicKey: string;
   signTransaction: SignTransactionFn;
   nftCollateralId: bigint;
   loanAmount: bigint;
   durationMonths: number;
+  asset?: string;
+  frequency?: PaymentFrequency;
 };
 
 export const requestLoan = async ({
@@ -226,12 +257,16 @@ export const requestLoan = async ({
   nftCollateralId,
   loanAmount,
   durationMonths,
+  asset = CONTRACTS.TEST_TOKEN,
+  frequency = PaymentFrequency.Monthly,
 }: RequestLoanParams) => {
   const args = [
     toScVal.address(publicKey),
     toScVal.u64(nftCollateralId),
     toScVal.i128(loanAmount),
     toScVal.u32(durationMonths),
+    toScVal.address(asset),
+    toScVal.u32(frequency),
   ];
 
   const { transaction } = await buildContractTransaction({
@@ -267,11 +302,20 @@ export const approveLoan = async ({
   return submitTransaction(signedTxXdr);
 };
 
+// Mirrors LoanManager's `ExcessPayment`: what happens to money beyond the current installment
+export const ExcessPayment = {
+  PayAhead: 0,
+  ReducePrincipal: 1,
+} as const;
+
+export type ExcessPayment = (typeof ExcessPayment)[keyof typeof ExcessPayment];
+
 type MakeLoanPaymentParams = {
   publicKey: string;
   signTransaction: SignTransactionFn;
   loanId: bigint;
   amount: bigint;
+  excess?: ExcessPayment;
 };
 
 export const makeLoanPayment = async ({
@@ -279,8 +323,13 @@ export const makeLoanPayment = async ({
   signTransaction,
   loanId,
   amount,
+  excess = ExcessPayment.PayAhead,
 }: MakeLoanPaymentParams) => {
-  const args = [toScVal.u64(loanId), toScVal.i128(amount)];
+  const args = [
+    toScVal.u64(loanId),
+    toScVal.i128(amount),
+    toScVal.u32(excess),
+  ];
 
   const { transaction } = await buildContractTransaction({
     contractId: CONTRACTS.LOAN_MANAGER,
@@ -307,6 +356,140 @@ export const getNFTData = async ({ tokenId, publicKey }: GetNFTDataParams) => {
   });
 };
 
+type GetVerificationParams = {
+  user: string;
+  publicKey: string;
+};
+
+// Resolves to the router's `Verification`: `state` is 0 not requested,
+// 1 pending, 2 verified or 3 failed, and `nft_id` is set once the oracle has
+// minted the reputation NFT.
+export const getVerification = async ({
+  user,
+  publicKey,
+}: GetVerificationParams) => {
+  return simulateContractCall({
+    contractId: CONTRACTS.RMTLEND,
+    method: "get_verification",
+    args: [toScVal.address(user)],
+    publicKey,
+  });
+};
+
+type RequestLoanWithQuoteParams = {
+  publicKey: string;
+  signTransaction: SignTransactionFn;
+  nftCollateralId: bigint;
+  loanAmount: bigint;
+  durationMonths: number;
+  maxAprBps: number;
+  asset?: string;
+  frequency?: PaymentFrequency;
+};
+
+// Opens a loan through the router, which refuses it if the quoted APR is
+// above `maxAprBps`.
+export const requestLoanWithQuote = async ({
+  publicKey,
+  signTransaction,
+  nftCollateralId,
+  loanAmount,
+  durationMonths,
+  maxAprBps,
+  asset = CONTRACTS.TEST_TOKEN,
+  frequency = PaymentFrequency.Monthly,
+}: RequestLoanWithQuoteParams) => {
+  // Struct fields are encoded as a map with its keys in sorted order
+  const request = xdr.ScVal.scvMap([
+    new xdr.ScMapEntry({
+      key: toScVal.symbol("amount"),
+      val: toScVal.i128(loanAmount),
+    }),
+    new xdr.ScMapEntry({
+      key: toScVal.symbol("asset"),
+      val: toScVal.address(asset),
+    }),
+    new xdr.ScMapEntry({
+      key: toScVal.symbol("duration_months"),
+      val: toScVal.u32(durationMonths),
+    }),
+    new xdr.ScMapEntry({
+      key: toScVal.symbol("frequency"),
+      val: toScVal.u32(frequency),
+    }),
+    new xdr.ScMapEntry({
+      key: toScVal.symbol("max_apr_bps"),
+      val: toScVal.u32(maxAprBps),
+    }),
+    new xdr.ScMapEntry({
+      key: toScVal.symbol("nft_id"),
+      val: toScVal.u64(nftCollateralId),
+    }),
+  ]);
+
+  const { transaction } = await buildContractTransaction({
+    contractId: CONTRACTS.RMTLEND,
+    method: "request_loan_with_quote",
+    args: [toScVal.address(publicKey), request],
+    publicKey,
+  });
+
+  const signedTxXdr = await signTransaction(transaction.toXDR());
+  return submitTransaction(signedTxXdr);
+};
+
+type RepayLoanParams = {
+  publicKey: string;
+  signTransaction: SignTransactionFn;
+  loanId: bigint;
+  amount: bigint;
+  excess?: ExcessPayment;
+};
+
+// Pays through the router; the payment that closes the loan also releases
+// the collateral NFT.
+export const repayLoan = async ({
+  publicKey,
+  signTransaction,
+  loanId,
+  amount,
+  excess = ExcessPayment.PayAhead,
+}: RepayLoanParams) => {
+  const args = [
+    toScVal.address(publicKey),
+    toScVal.u64(loanId),
+    toScVal.i128(amount),
+    toScVal.u32(excess),
+  ];
+
+  const { transaction } = await buildContractTransaction({
+    contractId: CONTRACTS.RMTLEND,
+    method: "repay",
+    args,
+    publicKey,
+  });
+
+  const signedTxXdr = await signTransaction(transaction.toXDR());
+  return submitTransaction(signedTxXdr);
+};
+
+type GetUserDashboardParams = {
+  user: string;
+  publicKey: string;
+};
+
+export const getUserDashboard = async ({
+  user,
+  publicKey,
+}: GetUserDashboardParams) => {
+  return simulateContractCall({
+    contractId: CONTRACTS.RMTLEND,
+    method: "get_user_dashboard",
+    args: [toScVal.address(user)],
+    publicKey,
+  });
+};
+
 type GetTokenCounterParams = {
   publicKey: string;
 };
@@ -340,16 +523,18 @@ export const getLoanDetails = async ({
 type GetLenderInfoParams = {
   lenderAddress: string;
   publicKey: string;
+  tranche?: Tranche;
 };
 
 export const getLenderInfo = async ({
   lenderAddress,
   publicKey,
+  tranche = Tranche.Senior,
 }: GetLenderInfoParams) => {
   return simulateContractCall({
     contractId: CONTRACTS.LENDING_POOL,
     method: "get_lender_info",
-    args: [toScVal.address(lenderAddress)],
+    args: [toScVal.address(lenderAddress), toScVal.u32(tranche)],
     publicKey,
   });
 };
@@ -25,16 +25,12 @@ export function useContractInteractions() {
     return result.signedTxXdr;
   };
 
-  const mintNFT = async ({
-    monthlyAmount,
-    reliabilityScore,
-    historyMonths,
-    totalSent,
+  const requestVerification = async ({
+    provider,
+    accountId,
   }: {
-    monthlyAmount: bigint;
-    reliabilityScore: number;
-    historyMonths: number;
-    totalSent: bigint;
+    provider: string;
+    accountId: string;
   }) => {
     if (!wallet?.publicKey) {
       throw new Error("Wallet not connected");
@@ -44,13 +40,11 @@ export function useContractInteractions() {
     setError(null);
 
     try {
-      const result = await contractInteractions.mintRemittanceNFT({
+      const result = await contractInteractions.requestVerification({
         publicKey: wallet.publicKey,
         signTransaction,
-        monthlyAmount,
-        reliabilityScore,
-        historyMonths,
-        totalSent,
+        provider,
+        accountId,
       });
 
       setIsLoading(false);
@@ -135,7 +129,11 @@ export function useContractInteractions() {
     }
   };
 
-  const depositToPool = async (amount: bigint): Promise<unknown> => {
+  const depositToPool = async (
+    amount: bigint,
+    tranche: contractInteractions.Tranche = contractInteractions.Tranche.Senior,
+    lockMonths = 0,
+  ): Promise<unknown> => {
     if (!wallet?.publicKey) {
       throw new Error("Wallet not connected");
     }
@@ -148,6 +146,8 @@ export function useContractInteractions() {
         publicKey: wallet.publicKey,
         signTransaction,
         amount,
+        tranche,
+        lockMonths,
       });
 
       setIsLoading(false);
@@ -160,7 +160,10 @@ export function useContractInteractions() {
     }
   };
 
-  const withdrawFromPool = async (amount: bigint): Promise<unknown> => {
+  const withdrawFromPool = async (
+    amount: bigint,
+    tranche: contractInteractions.Tranche = contractInteractions.Tranche.Senior,
+  ): Promise<unknown> => {
     if (!wallet?.publicKey) {
       throw new Error("Wallet not connected");
     }
@@ -173,6 +176,7 @@ export function useContractInteractions() {
         publicKey: wallet.publicKey,
         signTransaction,
         amount,
+        tranche,
       });
 
       setIsLoading(false);
@@ -189,10 +193,12 @@ export function useContractInteractions() {
     nftCollateralId,
     loanAmount,
     durationMonths,
+    frequency,
   }: {
     nftCollateralId: bigint;
     loanAmount: bigint;
     durationMonths: number;
+    frequency?: contractInteractions.PaymentFrequency;
   }): Promise<unknown> => {
     if (!wallet?.publicKey) {
       throw new Error("Wallet not connected");
@@ -208,6 +214,7 @@ export function useContractInteractions() {
         nftCollateralId,
         loanAmount,
         durationMonths,
+        frequency,
       });
 
       setIsLoading(false);
@@ -248,9 +255,11 @@ export function useContractInteractions() {
   const makeLoanPayment = async ({
     loanId,
     amount,
+    excess,
   }: {
     loanId: bigint;
     amount: bigint;
+    excess?: contractInteractions.ExcessPayment;
   }): Promise<unknown> => {
     if (!wallet?.publicKey) {
       throw new Error("Wallet not connected");
@@ -265,6 +274,7 @@ export function useContractInteractions() {
         signTransaction,
         loanId,
         amount,
+        excess,
       });
 
       setIsLoading(false);
@@ -325,7 +335,10 @@ export function useContractInteractions() {
     }
   };
 
-  const getLenderInfo = async (lenderAddress?: string): Promise<unknown> => {
+  const getLenderInfo = async (
+    lenderAddress?: string,
+    tranche: contractInteractions.Tranche = contractInteractions.Tranche.Senior,
+  ): Promise<unknown> => {
     // For read-only operations, we only need a public key for the query
     const queryPublicKey = wallet?.publicKey || wallet?.address;
     if (!queryPublicKey) {
@@ -338,6 +351,7 @@ export function useContractInteractions() {
       const result = await contractInteractions.getLenderInfo({
         lenderAddress: address,
         publicKey: queryPublicKey,
+        tranche,
       });
 
       return result as unknown;
@@ -406,21 +420,139 @@ export function useContractInteractions() {
     }
   };
 
+  const requestLoanWithQuote = async ({
+    nftCollateralId,
+    loanAmount,
+    durationMonths,
+    maxAprBps,
+    frequency,
+  }: {
+    nftCollateralId: bigint;
+    loanAmount: bigint;
+    durationMonths: number;
+    maxAprBps: number;
+    frequency?: contractInteractions.PaymentFrequency;
+  }): Promise<unknown> => {
+    if (!wallet?.publicKey) {
+      throw new Error("Wallet not connected");
+    }
+
+    setIsLoading(true);
+    setError(null);
+
+    try {
+      const result = await contractInteractions.requestLoanWithQuote({
+        publicKey: wallet.publicKey,
+        signTransaction,
+        nftCollateralId,
+        loanAmount,
+        durationMonths,
+        maxAprBps,
+        frequency,
+      });
+
+      setIsLoading(false);
+      return result as unknown;
+    } catch (err) {
+      const error = err as Error;
+      setError(error);
+      setIsLoading(false);
+      throw error;
+    }
+  };
+
+  const repayLoan = async ({
+    loanId,
+    amount,
+    excess,
+  }: {
+    loanId: bigint;
+    amount: bigint;
+    excess?: contractInteractions.ExcessPayment;
+  }): Promise<unknown> => {
+    if (!wallet?.publicKey) {
+      throw new Error("Wallet not connected");
+    }
+
+    setIsLoading(true);
+    setError(null);
+
+    try {
+      const result = await contractInteractions.repayLoan({
+        publicKey: wallet.publicKey,
+        signTransaction,
+        loanId,
+        amount,
+        excess,
+      });
+
+      setIsLoading(false);
+      return result as unknown;
+    } catch (err) {
+      const error = err as Error;
+      setError(error);
+      setIsLoading(false);
+      throw error;
+    }
+  };
+
+  const getVerification = async (): Promise<unknown> => {
+    const queryPublicKey = wallet?.publicKey || wallet?.address;
+    if (!queryPublicKey) {
+      throw new Error("Wallet not connected");
+    }
+
+    try {
+      const result = await contractInteractions.getVerification({
+        user: queryPublicKey,
+        publicKey: queryPublicKey,
+      });
+      return result as unknown;
+    } catch (err) {
+      const error = err as Error;
+      setError(error);
+      throw error;
+    }
+  };
+
+  const getUserDashboard = async (): Promise<unknown> => {
+    const queryPublicKey = wallet?.publicKey || wallet?.address;
+    if (!queryPublicKey) {
+      throw new Error("Wallet not connected");
+    }
+
+    try {
+      const result = await contractInteractions.getUserDashboard({
+        user: queryPublicKey,
+        publicKey: queryPublicKey,
+      });
+      return result as unknown;
+    } catch (err) {
+      const error = err as Error;
+      setError(error);
+      throw error;
+    }
+  };
+
   return {
     isLoading,
     error,
-    mintNFT,
+    requestVerification,
     depositToPool,
     withdrawFromPool,
     requestLoan,
     approveLoan,
     makeLoanPayment,
+    requestLoanWithQuote,
+    repayLoan,
     getNFTData,
     getLoanDetails,
     getLenderInfo,
     getAvailableLiquidity,
     getUtilizationRate,
     getTokenCounter,
+    getVerification,
+    getUserDashboard,
     getLendingAllowance,
     enableLendingAllowance,
     mintTestUSDC,
@@ -28,10 +28,11 @@ interface Loan {
   amount: number;
   outstandingBalance: number;
   interestRate: number;
-  monthlyPayment: number;
+  installmentAmount: number;
   nextPaymentDue: number;
   paymentsRemaining: number;
   totalPayments: number;
+  durationMonths: number;
   status: LoanStatusLabel;
   startTimestamp: number;
   borrower?: string;
@@ -123,19 +124,20 @@ const parseLoanStruct = (loanId: number, rawLoan: unknown): Loan => {
       ) / 100,
     interestRate:
       toNumber(native["interest_rate"] ?? native["interestRate"] ?? 0) / 100,
-    monthlyPayment:
+    installmentAmount:
       Math.round(
         lumensFromStroops(
-          native["monthly_payment"] ?? native["monthlyPayment"] ?? 0,
+          native["installment_amount"] ?? native["installmentAmount"] ?? 0,
         ) * 100,
       ) / 100,
     nextPaymentDue: toNumber(native["next_payment_due"] ?? 0) * 1000,
     paymentsRemaining: Math.max(
       0,
-      toNumber(native["duration_months"] ?? 0) -
+      toNumber(native["installments"] ?? 0) -
         toNumber(native["payments_made"] ?? 0),
     ),
-    totalPayments: toNumber(native["duration_months"] ?? 0),
+    totalPayments: toNumber(native["installments"] ?? 0),
+    durationMonths: toNumber(native["duration_months"] ?? 0),
     status:
       typeof native.status === "string"
         ? (native.status as LoanStatusLabel)
@@ -491,7 +493,7 @@ const BorrowerDashboard: React.FC = () => {
 
     try {
       const amountInStroops = BigInt(
-        Math.floor(loan.monthlyPayment * 10_000_000),
+        Math.floor(loan.installmentAmount * 10_000_000),
       );
       const result = await makeLoanPayment({
         loanId: BigInt(loan.loanId),
@@ -621,10 +623,10 @@ const BorrowerDashboard: React.FC = () => {
                     },
                     {
                       icon: CreditCard,
-                      label: "Monthly Payment",
+                      label: "Installment",
                       value:
                         loanData.length > 0
-                          ? `$${loanData[0].monthlyPayment.toLocaleString()}`
+                          ? `$${loanData[0].installmentAmount.toLocaleString()}`
                           : "$0",
                       color: "bg-orange-500",
                     },
@@ -905,8 +907,8 @@ const BorrowerDashboard: React.FC = () => {
                             value: `${loan.interestRate}% APR`,
                           },
                           {
-                            label: "Monthly Payment",
-                            value: `${loan.monthlyPayment.toLocaleString()} XLM`,
+                            label: "Installment",
+                            value: `${loan.installmentAmount.toLocaleString()} XLM`,
                           },
                           {
                             label: "Next Payment Due",
@@ -924,7 +926,7 @@ const BorrowerDashboard: React.FC = () => {
                             label: "Estimated Maturity",
                             value: formatDate(
                               loan.startTimestamp +
-                                loan.totalPayments * 30 * 24 * 60 * 60 * 1000,
+                                loan.durationMonths * 30 * 24 * 60 * 60 * 1000,
                             ),
                           },
                         ].map((item, index) => (
@@ -1,4 +1,4 @@
-import React, { useState } from "react";
+import React, { useEffect, useState } from "react";
 import { useWallet } from "../hooks/useWallet";
 import { useContractInteractions } from "../hooks/useContractInteractions";
 import { CheckCircle, Clock, AlertCircle, Award, Loader2 } from "lucide-react";
@@ -12,12 +12,22 @@ interface VerificationData {
   historyMonths: number;
   totalSent: number;
   reliabilityScore: number;
-  nftTokenId?: number;
+  nftTokenId: number;
 }
 
+// VerificationState values returned by the RmtLend router
+const STATE_VERIFIED = 2;
+const STATE_FAILED = 3;
+const POLL_INTERVAL_MS = 5000;
+
 const VerificationFlow: React.FC = () => {
   const { connected } = useWallet();
-  const { mintNFT, isLoading } = useContractInteractions();
+  const {
+    requestVerification,
+    getVerification,
+    getNFTData,
+    isLoading,
+  } = useContractInteractions();
   const [currentStep, setCurrentStep] = useState<VerificationStep>("connect");
   const [selectedProvider, setSelectedProvider] = useState("");
   const [accountId, setAccountId] = useState("");
@@ -42,63 +52,13 @@ const VerificationFlow: React.FC = () => {
     setVerificationError(null);
 
     try {
-      // In a real implementation, this would call the oracle_verifier contract
-      // to request verification first, then the oracle would call back to mint the NFT
-
-      // Simulate oracle verification delay
-      await new Promise((resolve) => setTimeout(resolve, 2000));
-
-      // Mock verified data (in production, this comes from the oracle)
-      const reliabilityScore = 70 + Math.floor(Math.random() * 31); // 70-100 inclusive
-
-      const mockData: VerificationData = {
+      // File the request with the oracle_verifier contract; the oracle mints
+      // (or refreshes) the NFT once the provider confirms the history, and the
+      // processing step polls until it has.
+      await requestVerification({
         provider: selectedProvider,
-        accountId: accountId,
-        monthlyAmount: 2500,
-        historyMonths: 18,
-        totalSent: 45000,
-        reliabilityScore,
-      };
-
-      // Mint the NFT on-chain
-      const result = await mintNFT({
-        monthlyAmount: BigInt(mockData.monthlyAmount * 10_000_000), // Convert to stroops
-        reliabilityScore: mockData.reliabilityScore,
-        historyMonths: mockData.historyMonths,
-        totalSent: BigInt(mockData.totalSent * 10_000_000), // Convert to stroops
-      });
-
-      console.log("Mint NFT result:", result);
-
-      // Extract token ID from result
-      let tokenId = 1; // Default fallback
-      try {
-        const rawResult = result?.result;
-        if (typeof rawResult === "bigint") {
-          tokenId = Number(rawResult);
-        } else if (typeof rawResult === "number") {
-          tokenId = rawResult;
-        } else if (typeof rawResult === "string") {
-          tokenId = parseInt(rawResult, 10);
-        }
-        console.log("Extracted token ID:", tokenId);
-      } catch (parseErr) {
-        console.error("Error parsing token ID:", parseErr);
-      }
-
-      setVerificationData({
-        ...mockData,
-        nftTokenId: tokenId,
+        accountId,
       });
-
-      if (typeof window !== "undefined") {
-        window.localStorage.setItem(
-          "remitlend_last_nft_id",
-          tokenId.toString(),
-        );
-      }
-
-      setCurrentStep("complete");
     } catch (err) {
       console.error("Verification failed:", err);
       setVerificationError(
@@ -108,6 +68,62 @@ const VerificationFlow: React.FC = () => {
     }
   };
 
+  // Stay on the processing step until the oracle has minted the NFT, then show
+  // the data the contract actually stored.
+  useEffect(() => {
+    if (currentStep !== "processing" || isLoading) return;
+
+    let cancelled = false;
+
+    const checkMinted = async () => {
+      try {
+        const verification = (await getVerification()) as {
+          state: number;
+          nft_id?: bigint | null;
+        };
+        if (cancelled) return;
+        if (Number(verification.state) === STATE_FAILED) {
+          setVerificationError("The oracle could not verify this account.");
+          setCurrentStep("failed");
+          return;
+        }
+        if (Number(verification.state) !== STATE_VERIFIED) return;
+
+        const tokenId = verification.nft_id;
+        if (tokenId === null || tokenId === undefined) return;
+
+        const data = (await getNFTData(BigInt(tokenId))) as {
+          monthly_amount: bigint;
+          history_months: number;
+          total_sent: bigint;
+          reliability_score: number;
+        };
+        if (cancelled) return;
+
+        setVerificationData({
+          provider: selectedProvider,
+          accountId,
+          monthlyAmount: Number(data.monthly_amount),
+          historyMonths: Number(data.history_months),
+          totalSent: Number(data.total_sent),
+          reliabilityScore: Number(data.reliability_score),
+          nftTokenId: Number(tokenId),
+        });
+        setCurrentStep("complete");
+      } catch {
+        // The request may not be visible yet; try again on the next tick
+      }
+    };
+
+    void checkMinted();
+    const interval = setInterval(() => void checkMinted(), POLL_INTERVAL_MS);
+    return () => {
+      cancelled = true;
+      clearInterval(interval);
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [currentStep, isLoading, selectedProvider, accountId]);
+
   const getScoreColor = (score: number) => {
     if (score >= 90) return "#10b981";
     if (score >= 80) return "#fbbf24";
@@ -365,10 +381,8 @@ const VerificationFlow: React.FC = () => {
 
               <div className="glass bg-white/5 dark:bg-white/5 rounded-xl p-4 border border-gray-300 dark:border-white/10">
                 <p className="text-gray-700 dark:text-white">
-                  Estimated time remaining:{" "}
-                  <strong className="text-gray-900 dark:text-white">
-                    2 minutes
-                  </strong>
+                  Waiting for the oracle to mint your NFT. This page updates
+                  automatically once it is on-chain.
                 </p>
               </div>
             </div>
@@ -8,6 +8,7 @@ interface ImportMetaEnv {
   readonly PUBLIC_REMITTANCE_NFT_CONTRACT_ID: string;
   readonly PUBLIC_LENDING_POOL_CONTRACT_ID: string;
   readonly PUBLIC_LOAN_MANAGER_CONTRACT_ID: string;
+  readonly PUBLIC_ORACLE_VERIFIER_CONTRACT_ID: string;
   readonly PUBLIC_RMTLEND_CONTRACT_ID: string;
 }
 